@@ -0,0 +1,180 @@
+//! Startup flags
+//!
+//! Command-line flags that override session defaults at launch. Overrides
+//! apply to the running session only and are not written to the config file.
+
+use crate::cli::commands::{self, format_error, Command, CommandType};
+use crate::config::storage::Config;
+use crate::config::{create_shared_state, SharedState};
+use crate::error::{Result, SchemaForgeError};
+use clap::Parser;
+
+/// Schema-Forge command-line arguments
+#[derive(Debug, Clone, Default, Parser)]
+#[command(name = "schema-forge", version, about)]
+pub struct CliArgs {
+    /// LLM provider to use for this session (must already be configured)
+    #[arg(long, value_name = "PROVIDER")]
+    pub provider: Option<String>,
+    /// Model to use with the session's provider
+    #[arg(long, value_name = "MODEL")]
+    pub model: Option<String>,
+    /// Database URL to connect to at startup (defaults to $DATABASE_URL)
+    #[arg(long = "db", value_name = "URL")]
+    pub database_url: Option<String>,
+    /// Run a single command, print its output, and exit
+    #[arg(long, value_name = "COMMAND")]
+    pub exec: Option<String>,
+    /// Skip the welcome and informational notes (same as /config quiet on)
+    #[arg(long)]
+    pub quiet: bool,
+    /// Retry failed LLM requests at most this many times; 0 fails fast
+    /// (same as /config retries)
+    #[arg(long, value_name = "N")]
+    pub max_retries: Option<u32>,
+    /// Read and write configuration in this directory (defaults to
+    /// $SCHEMA_FORGE_CONFIG_DIR, then the OS config directory)
+    #[arg(long, value_name = "PATH")]
+    pub config_dir: Option<std::path::PathBuf>,
+}
+
+impl CliArgs {
+    /// Build the shared state for a session, applying flag overrides
+    pub async fn build_state(&self) -> Result<SharedState> {
+        self.build_state_with_env_url(std::env::var("DATABASE_URL").ok())
+            .await
+    }
+
+    /// Build the session state, falling back to `env_url` (`$DATABASE_URL`)
+    /// when `--db` is not given
+    ///
+    /// A failing `--db` stops startup; a failing `DATABASE_URL` only warns,
+    /// since the variable is often set for other tools.
+    pub async fn build_state_with_env_url(&self, env_url: Option<String>) -> Result<SharedState> {
+        if let Some(ref dir) = self.config_dir {
+            Config::set_config_dir(dir.clone());
+        }
+        let state = create_shared_state();
+
+        {
+            let mut state_guard = state.write().await;
+            if self.quiet {
+                state_guard.overrides.quiet = Some(true);
+            }
+            if let Some(retries) = self.max_retries {
+                state_guard.overrides.retries = Some(retries);
+            }
+            if let Some(ref provider) = self.provider {
+                let provider = provider.to_lowercase();
+                if !state_guard.api_keys.contains_key(&provider) {
+                    return Err(SchemaForgeError::InvalidInput(format!(
+                        "Provider '{}' is not configured. Run /config {} first.",
+                        provider, provider
+                    )));
+                }
+                state_guard.overrides.provider = Some(provider);
+            }
+
+            if let Some(ref model) = self.model {
+                let provider = state_guard.get_current_provider().cloned().ok_or_else(|| {
+                    SchemaForgeError::InvalidInput(
+                        "--model needs a provider. Pass --provider or configure one first."
+                            .to_string(),
+                    )
+                })?;
+                state_guard.overrides.models.insert(provider, model.clone());
+            }
+        }
+
+        if let Some(ref url) = self.database_url {
+            let connect = Command::from(CommandType::Connect {
+                url: url.clone(),
+                label: None,
+            });
+            commands::handle_command(&connect, state.clone()).await?;
+        } else if let Some(url) = env_url.filter(|url| !url.trim().is_empty()) {
+            if state.read().await.settings.auto_connect {
+                let connect = Command::from(CommandType::Connect {
+                    url: url.trim().to_string(),
+                    label: None,
+                });
+                match commands::handle_command(&connect, state.clone()).await {
+                    Ok(_) if state.read().await.quiet() => {}
+                    Ok(summary) => eprintln!("DATABASE_URL: {}", summary),
+                    Err(error) => eprintln!("DATABASE_URL not connected. {}", format_error(&error)),
+                }
+            }
+        }
+
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_startup_flags() {
+        let args = CliArgs::parse_from([
+            "schema-forge",
+            "--provider",
+            "groq",
+            "--model",
+            "llama-3.3-70b-versatile",
+            "--db",
+            "sqlite://demo.db",
+            "--exec",
+            "/help",
+            "--quiet",
+            "--max-retries",
+            "0",
+            "--config-dir",
+            "/tmp/schema-forge",
+        ]);
+        assert_eq!(args.provider.as_deref(), Some("groq"));
+        assert_eq!(args.model.as_deref(), Some("llama-3.3-70b-versatile"));
+        assert_eq!(args.database_url.as_deref(), Some("sqlite://demo.db"));
+        assert_eq!(args.exec.as_deref(), Some("/help"));
+        assert!(args.quiet);
+        assert_eq!(args.max_retries, Some(0));
+        assert_eq!(
+            args.config_dir.as_deref(),
+            Some(std::path::Path::new("/tmp/schema-forge"))
+        );
+
+        let args = CliArgs::parse_from(["schema-forge"]);
+        assert!(args.exec.is_none());
+        assert!(!args.quiet);
+        assert!(args.max_retries.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_flag_overrides_stay_in_session() {
+        let args = CliArgs::parse_from(["schema-forge", "--quiet", "--max-retries", "0"]);
+        let state = args.build_state_with_env_url(None).await.unwrap();
+        let state_guard = state.read().await;
+        assert_eq!(state_guard.overrides.retries, Some(0));
+        assert_eq!(state_guard.retries(), 0);
+        assert_eq!(state_guard.overrides.quiet, Some(true));
+        assert!(state_guard.quiet());
+    }
+
+    #[tokio::test]
+    async fn test_database_url_connects_at_startup() {
+        let args = CliArgs::default();
+        let state = args
+            .build_state_with_env_url(Some("sqlite::memory:".to_string()))
+            .await
+            .unwrap();
+        let auto_connect = state.read().await.settings.auto_connect;
+        assert_eq!(state.read().await.is_connected(), auto_connect);
+
+        // A broken DATABASE_URL warns instead of failing startup
+        let state = args
+            .build_state_with_env_url(Some("redis://localhost".to_string()))
+            .await
+            .unwrap();
+        assert!(!state.read().await.is_connected());
+    }
+}
@@ -0,0 +1,146 @@
+//! Clipboard support for `/copy`
+//!
+//! The system clipboard sits behind [`ClipboardSink`] so the selection logic
+//! can be tested without a display server.
+
+use crate::config::AppState;
+use crate::error::{Result, SchemaForgeError};
+use std::sync::{Mutex, PoisonError};
+
+/// What `/copy` should place on the clipboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyTarget {
+    /// The most recently executed SQL statement
+    Sql,
+    /// The most recent result, formatted as a table
+    Results,
+}
+
+impl std::str::FromStr for CopyTarget {
+    type Err = SchemaForgeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "sql" => Ok(CopyTarget::Sql),
+            "results" | "result" => Ok(CopyTarget::Results),
+            _ => Err(SchemaForgeError::InvalidCommandSyntax {
+                command: "/copy".to_string(),
+                expected: "/copy sql|results".to_string(),
+            }),
+        }
+    }
+}
+
+/// Destination for copied text
+pub trait ClipboardSink {
+    /// Replace the clipboard contents with `text`
+    fn set_text(&mut self, text: String) -> Result<()>;
+}
+
+/// The operating system clipboard
+pub struct SystemClipboard;
+
+/// Held open for the rest of the session once `/copy` first uses it
+///
+/// On X11 and Wayland the copied text is served by this process, and it
+/// disappears as soon as the last `arboard::Clipboard` is dropped.
+static CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+
+impl ClipboardSink for SystemClipboard {
+    fn set_text(&mut self, text: String) -> Result<()> {
+        let mut open = CLIPBOARD.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut clipboard = match open.take() {
+            Some(clipboard) => clipboard,
+            None => arboard::Clipboard::new().map_err(clipboard_unavailable)?,
+        };
+        let result = clipboard.set_text(text).map_err(clipboard_unavailable);
+        *open = Some(clipboard);
+        result
+    }
+}
+
+fn clipboard_unavailable(error: arboard::Error) -> SchemaForgeError {
+    SchemaForgeError::InvalidInput(format!(
+        "Clipboard is not available ({}). In a headless session, copy from the transcript instead.",
+        error
+    ))
+}
+
+/// Pick the text for a copy target from the session state
+pub fn select_copy_text(state: &AppState, target: CopyTarget) -> Result<String> {
+    match target {
+        CopyTarget::Sql => state.last_sql.clone().ok_or_else(|| {
+            SchemaForgeError::InvalidInput("No SQL has been run yet.".to_string())
+        }),
+        CopyTarget::Results => state
+            .last_result
+            .as_ref()
+            .map(|result| result.render_with_null(None, &state.settings.null_display))
+            .ok_or_else(|| {
+                SchemaForgeError::InvalidInput("No query result to copy yet.".to_string())
+            }),
+    }
+}
+
+/// Copy the selected text into `sink` and describe what was copied
+pub fn copy_to(sink: &mut dyn ClipboardSink, state: &AppState, target: CopyTarget) -> Result<String> {
+    let text = select_copy_text(state, target)?;
+    let length = text.chars().count();
+    sink.set_text(text)?;
+
+    let label = match target {
+        CopyTarget::Sql => "SQL",
+        CopyTarget::Results => "results",
+    };
+    Ok(format!("Copied {} to the clipboard ({} characters)", label, length))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::results::QueryResult;
+
+    #[derive(Default)]
+    struct MockClipboard {
+        contents: Option<String>,
+    }
+
+    impl ClipboardSink for MockClipboard {
+        fn set_text(&mut self, text: String) -> Result<()> {
+            self.contents = Some(text);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_copy_selects_sql_or_results() {
+        let mut state = AppState::default();
+        let mut clipboard = MockClipboard::default();
+
+        assert!(copy_to(&mut clipboard, &state, CopyTarget::Sql).is_err());
+        assert!(copy_to(&mut clipboard, &state, CopyTarget::Results).is_err());
+        assert!(clipboard.contents.is_none());
+
+        state.last_sql = Some("SELECT name FROM users".to_string());
+        state.last_result = Some(QueryResult {
+            columns: vec!["name".to_string()],
+            rows: vec![vec!["Alice".to_string()]],
+            message: None,
+            truncated_at: None,
+            column_types: Vec::new(),
+        });
+
+        copy_to(&mut clipboard, &state, CopyTarget::Sql).unwrap();
+        assert_eq!(clipboard.contents.as_deref(), Some("SELECT name FROM users"));
+
+        copy_to(&mut clipboard, &state, CopyTarget::Results).unwrap();
+        assert!(clipboard.contents.as_deref().unwrap().contains("Alice"));
+    }
+
+    #[test]
+    fn test_copy_target_from_str() {
+        assert_eq!("sql".parse::<CopyTarget>().unwrap(), CopyTarget::Sql);
+        assert_eq!("Results".parse::<CopyTarget>().unwrap(), CopyTarget::Results);
+        assert!("table".parse::<CopyTarget>().is_err());
+    }
+}
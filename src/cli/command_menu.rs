@@ -1,5 +1,7 @@
 //! Docked slash command menu for the persistent TUI.
 
+use crate::cli::command_spec;
+use crate::llm::provider_registry;
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
@@ -17,62 +19,15 @@ pub struct CommandItem {
 }
 
 pub fn command_items() -> Vec<CommandItem> {
-    vec![
-        CommandItem {
-            name: "/connect",
-            description: "Connect to a database",
-            example: "/connect sqlite://demo.db",
-            requires_arguments: true,
-        },
-        CommandItem {
-            name: "/index",
-            description: "Index the database schema",
-            example: "/index",
-            requires_arguments: false,
-        },
-        CommandItem {
-            name: "/config",
-            description: "Configure a hosted LLM or local Ollama",
-            example: "/config ollama",
-            requires_arguments: true,
-        },
-        CommandItem {
-            name: "/providers",
-            description: "List configured and available providers",
-            example: "/providers",
-            requires_arguments: false,
-        },
-        CommandItem {
-            name: "/use",
-            description: "Switch to a configured provider",
-            example: "/use groq",
-            requires_arguments: true,
-        },
-        CommandItem {
-            name: "/model",
-            description: "Set the model for a provider",
-            example: "/model openai gpt-4o",
-            requires_arguments: true,
-        },
-        CommandItem {
-            name: "/clear",
-            description: "Clear the current transcript",
-            example: "/clear",
-            requires_arguments: false,
-        },
-        CommandItem {
-            name: "/help",
-            description: "Show command help",
-            example: "/help",
-            requires_arguments: false,
-        },
-        CommandItem {
-            name: "/quit",
-            description: "Exit Schema-Forge",
-            example: "/quit",
-            requires_arguments: false,
-        },
-    ]
+    command_spec::command_specs()
+        .iter()
+        .map(|spec| CommandItem {
+            name: spec.name,
+            description: spec.summary,
+            example: spec.example,
+            requires_arguments: spec.requires_arguments,
+        })
+        .collect()
 }
 
 pub fn filtered_commands(input: &str) -> Vec<CommandItem> {
@@ -100,6 +55,203 @@ pub fn apply_command(command: &CommandItem) -> String {
     }
 }
 
+/// Commands whose argument can be picked from a list instead of typed
+pub const PICKER_COMMANDS: &[&str] = &["/use", "/model"];
+
+/// What the secondary picker is choosing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PickerStage {
+    /// A configured provider, for `/use` or `/model`
+    Provider { command: &'static str },
+    /// A known model of the chosen provider
+    Model { provider: String },
+}
+
+/// Outcome of accepting a picker entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MenuResult {
+    /// A fully-formed command, ready to submit
+    Command(String),
+    /// A partial command for the user to finish typing
+    Input(String),
+    /// The picker moved on to its next list
+    Next,
+    /// Nothing matched the filter
+    Nothing,
+}
+
+/// Secondary list opened from `/use` or `/model`
+#[derive(Debug, Clone)]
+pub struct Picker {
+    pub stage: PickerStage,
+    pub filter: String,
+    pub state: ListState,
+    options: Vec<String>,
+}
+
+impl Picker {
+    /// Open the provider list for `command`
+    ///
+    /// Returns `None` for commands without a picker or when no provider is
+    /// configured yet.
+    pub fn open(command: &str, providers: &[String]) -> Option<Self> {
+        let command = PICKER_COMMANDS.iter().find(|name| **name == command)?;
+        if providers.is_empty() {
+            return None;
+        }
+        Some(Self {
+            stage: PickerStage::Provider { command: *command },
+            filter: String::new(),
+            state: ListState::default().with_selected(Some(0)),
+            options: providers.to_vec(),
+        })
+    }
+
+    /// Entries matching the current filter
+    pub fn filtered(&self) -> Vec<&str> {
+        filter_options(&self.options, &self.filter)
+    }
+
+    /// Title shown above the list
+    pub fn title(&self) -> String {
+        match &self.stage {
+            PickerStage::Provider { command } => format!(" {}: pick a provider ", command),
+            PickerStage::Model { provider } => format!(" /model {}: pick a model ", provider),
+        }
+    }
+
+    /// Narrow the list by one more typed character
+    pub fn push_filter(&mut self, ch: char) {
+        self.filter.push(ch);
+        self.sync_selection();
+    }
+
+    /// Remove the last typed filter character
+    pub fn pop_filter(&mut self) {
+        self.filter.pop();
+        self.sync_selection();
+    }
+
+    pub fn select_previous(&mut self) {
+        let selected = self.state.selected().unwrap_or(0).saturating_sub(1);
+        self.state.select(Some(selected));
+        self.sync_selection();
+    }
+
+    pub fn select_next(&mut self) {
+        let selected = self.state.selected().map_or(0, |selected| selected + 1);
+        self.state.select(Some(selected));
+        self.sync_selection();
+    }
+
+    /// Take the highlighted entry
+    ///
+    /// `/use` finishes at once; `/model` moves on to that provider's models,
+    /// or leaves `/model <provider> ` to type when none are known.
+    pub fn accept(&mut self) -> MenuResult {
+        let Some(choice) = self
+            .state
+            .selected()
+            .and_then(|selected| self.filtered().get(selected).map(|choice| choice.to_string()))
+        else {
+            return MenuResult::Nothing;
+        };
+
+        match &self.stage {
+            PickerStage::Provider { command: "/model" } => {
+                let models = model_options(&choice);
+                if models.is_empty() {
+                    return MenuResult::Input(format!("/model {} ", choice));
+                }
+                self.stage = PickerStage::Model { provider: choice };
+                self.options = models;
+                self.filter.clear();
+                self.state.select(Some(0));
+                MenuResult::Next
+            }
+            PickerStage::Provider { command } => MenuResult::Command(format!("{} {}", command, choice)),
+            PickerStage::Model { provider } => {
+                MenuResult::Command(format!("/model {} {}", provider, choice))
+            }
+        }
+    }
+
+    fn sync_selection(&mut self) {
+        let count = self.filtered().len();
+        if count == 0 {
+            self.state.select(None);
+        } else {
+            let selected = self.state.selected().unwrap_or(0).min(count - 1);
+            self.state.select(Some(selected));
+        }
+    }
+}
+
+/// Options containing `filter`, ignoring case
+pub fn filter_options<'a>(options: &'a [String], filter: &str) -> Vec<&'a str> {
+    let needle = filter.trim().to_lowercase();
+    options
+        .iter()
+        .map(String::as_str)
+        .filter(|option| option.to_lowercase().contains(needle.as_str()))
+        .collect()
+}
+
+/// Well-known models for a provider, from the provider registry
+pub fn model_options(provider: &str) -> Vec<String> {
+    provider_registry::find_provider(provider)
+        .map(|descriptor| descriptor.models.iter().map(|model| model.to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// Draw the provider/model picker in place of the command dock
+pub fn render_picker(frame: &mut Frame, area: Rect, picker: &mut Picker) {
+    let sections = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(2),
+        ])
+        .split(area);
+
+    let options = picker.filtered();
+    let items = if options.is_empty() {
+        vec![ListItem::new(Line::from(Span::styled(
+            "No matches.",
+            Style::default().fg(Color::DarkGray),
+        )))]
+    } else {
+        options
+            .iter()
+            .map(|option| ListItem::new(Line::from(option.to_string())))
+            .collect()
+    };
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan))
+                .title(picker.title()),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        );
+    frame.render_stateful_widget(list, sections[0], &mut picker.state);
+
+    let footer = Paragraph::new(Line::from(format!("Filter: {}", picker.filter)))
+        .block(
+            Block::default()
+                .borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM)
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .wrap(Wrap { trim: true });
+    frame.render_widget(footer, sections[1]);
+}
+
 pub fn render_command_dock(
     frame: &mut Frame,
     area: Rect,
@@ -182,9 +334,74 @@ mod tests {
         assert_eq!(commands[0].name, "/model");
     }
 
+    #[test]
+    fn test_menu_candidates_match_command_spec() {
+        let candidates: Vec<&str> = filtered_commands("/").iter().map(|command| command.name).collect();
+        let spec_names: Vec<&str> = command_spec::command_specs()
+            .iter()
+            .map(|spec| spec.name)
+            .collect();
+        assert_eq!(candidates, spec_names);
+    }
+
     #[test]
     fn test_apply_command_adds_trailing_space_for_args() {
         let command = filtered_commands("/connect").remove(0);
         assert_eq!(apply_command(&command), "/connect ");
     }
+
+    fn providers() -> Vec<String> {
+        vec!["anthropic".to_string(), "groq".to_string(), "openai".to_string()]
+    }
+
+    #[test]
+    fn test_filter_options_ignores_case() {
+        let providers = providers();
+        assert_eq!(filter_options(&providers, "OP"), vec!["openai"]);
+        assert_eq!(filter_options(&providers, "").len(), 3);
+        assert!(filter_options(&providers, "zzz").is_empty());
+    }
+
+    #[test]
+    fn test_picker_only_opens_for_provider_commands() {
+        assert!(Picker::open("/use", &providers()).is_some());
+        assert!(Picker::open("/connect", &providers()).is_none());
+        assert!(Picker::open("/model", &[]).is_none());
+    }
+
+    #[test]
+    fn test_use_picker_returns_command() {
+        let mut picker = Picker::open("/use", &providers()).unwrap();
+        picker.push_filter('g');
+        assert_eq!(picker.filtered(), vec!["groq"]);
+        assert_eq!(picker.accept(), MenuResult::Command("/use groq".to_string()));
+    }
+
+    #[test]
+    fn test_model_picker_moves_on_to_models() {
+        let mut picker = Picker::open("/model", &providers()).unwrap();
+        picker.select_next();
+        picker.select_next();
+        assert_eq!(picker.accept(), MenuResult::Next);
+        assert_eq!(picker.stage, PickerStage::Model { provider: "openai".to_string() });
+        assert_eq!(picker.filtered().first(), Some(&"gpt-4o"));
+
+        picker.push_filter('m');
+        picker.push_filter('i');
+        assert_eq!(picker.accept(), MenuResult::Command("/model openai gpt-4o-mini".to_string()));
+    }
+
+    #[test]
+    fn test_picker_selection_stays_in_range() {
+        let mut picker = Picker::open("/use", &providers()).unwrap();
+        for _ in 0..5 {
+            picker.select_next();
+        }
+        assert_eq!(picker.state.selected(), Some(2));
+        picker.push_filter('x');
+        assert_eq!(picker.state.selected(), None);
+        assert_eq!(picker.accept(), MenuResult::Nothing);
+        picker.pop_filter();
+        assert_eq!(picker.state.selected(), Some(0));
+    }
 }
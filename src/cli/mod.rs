@@ -3,10 +3,15 @@
 //! This module provides the command-line interface for Schema-Forge,
 //! including the REPL implementation and command handlers.
 
+pub mod args;
+pub mod clipboard;
 pub mod command_menu;
+pub mod command_spec;
 pub mod commands;
 pub mod repl;
+pub mod shutdown;
 pub mod tui;
 
 // Re-exports
+pub use args::CliArgs;
 pub use repl::Repl;
@@ -0,0 +1,336 @@
+//! Orderly exit for the session
+//!
+//! `/quit`, Ctrl+C, Ctrl+D on an empty prompt and SIGTERM all end in
+//! [`shutdown`], which saves the input history, flushes output and closes
+//! the database pool so connections are released before the process exits.
+
+use crate::config::settings::Settings;
+use crate::config::storage::{write_private, Config};
+use crate::config::SharedState;
+use crate::database::manager::DatabaseManager;
+use crate::error::Result;
+use async_trait::async_trait;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::Notify;
+
+/// Input history file in the config directory
+pub const HISTORY_FILE: &str = "history";
+/// Most recent inputs kept between sessions
+pub const HISTORY_LIMIT: usize = 1000;
+
+/// A shutdown request from outside the session
+///
+/// One instance, [`TERMINATE`], serves the process; tests use their own so
+/// they never leave the global flag set.
+struct TerminateSignal {
+    /// Set once a shutdown is requested; the TUI checks it between events
+    requested: AtomicBool,
+    /// Wakes anything waiting in [`TerminateSignal::wait`], such as a running command
+    notify: Notify,
+}
+
+impl TerminateSignal {
+    const fn new() -> Self {
+        Self {
+            requested: AtomicBool::new(false),
+            notify: Notify::const_new(),
+        }
+    }
+
+    fn request(&self) {
+        self.requested.store(true, Ordering::SeqCst);
+        self.notify.notify_waiters();
+    }
+
+    fn is_requested(&self) -> bool {
+        self.requested.load(Ordering::SeqCst)
+    }
+
+    async fn wait(&self) {
+        let notified = self.notify.notified();
+        tokio::pin!(notified);
+        // Register before checking the flag so a request in between is not missed
+        notified.as_mut().enable();
+        if self.is_requested() {
+            return;
+        }
+        notified.await;
+    }
+}
+
+/// Set by SIGTERM
+static TERMINATE: TerminateSignal = TerminateSignal::new();
+
+/// Ask the running session to shut down
+pub fn request_terminate() {
+    TERMINATE.request();
+}
+
+/// Check whether a shutdown was requested from outside the session
+pub fn terminate_requested() -> bool {
+    TERMINATE.is_requested()
+}
+
+/// Wait until a shutdown is requested from outside the session
+///
+/// Returns at once if one already was. The TUI races running commands
+/// against this, so SIGTERM does not wait for a slow query or LLM call.
+pub async fn terminated() {
+    TERMINATE.wait().await;
+}
+
+/// Where shutdown hands off session state
+#[async_trait]
+pub trait ShutdownSinks: Send {
+    /// Persist the input history
+    fn save_history(&mut self, history: &[String]) -> Result<()>;
+    /// Release the database connections
+    async fn close_database(&mut self, manager: &DatabaseManager);
+}
+
+/// Sinks for a real session: the history file and the live pool
+pub struct SessionSinks;
+
+#[async_trait]
+impl ShutdownSinks for SessionSinks {
+    fn save_history(&mut self, history: &[String]) -> Result<()> {
+        save_history_to(&history_path()?, history)
+    }
+
+    async fn close_database(&mut self, manager: &DatabaseManager) {
+        manager.close().await;
+    }
+}
+
+/// Save history, flush output and close the database pool
+///
+/// An empty history is not written, so a one-off `--exec` run keeps the
+/// saved one. The pool is closed even when saving fails; that error is
+/// returned afterwards.
+pub async fn shutdown(
+    state: &SharedState,
+    history: &[String],
+    sinks: &mut dyn ShutdownSinks,
+) -> Result<()> {
+    let saved = if history.is_empty() {
+        Ok(())
+    } else {
+        sinks.save_history(history)
+    };
+    let _ = std::io::stdout().flush();
+    let _ = std::io::stderr().flush();
+
+    // Taken out of the state so nothing can query a closed pool
+    let manager = state.write().await.database_manager.take();
+    if let Some(manager) = manager {
+        sinks.close_database(&manager).await;
+    }
+    saved
+}
+
+/// Path of the input history file
+pub fn history_path() -> Result<PathBuf> {
+    Ok(Config::config_dir()?.join(HISTORY_FILE))
+}
+
+/// Inputs saved by earlier sessions, oldest first
+pub fn load_history() -> Vec<String> {
+    history_path()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .map(|content| {
+            content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Write the last [`HISTORY_LIMIT`] single-line inputs to `path`
+///
+/// API keys, tokens, header values and URL passwords are masked with
+/// [`redact_history_entry`] first; the file is still owner-only.
+pub fn save_history_to(path: &Path, history: &[String]) -> Result<()> {
+    let lines: Vec<String> = history
+        .iter()
+        .filter(|entry| !entry.contains('\n'))
+        .map(|entry| redact_history_entry(entry))
+        .collect();
+    let kept = &lines[lines.len().saturating_sub(HISTORY_LIMIT)..];
+    let mut content = kept.join("\n");
+    content.push('\n');
+    write_private(path, content.as_bytes())?;
+    Ok(())
+}
+
+/// Stands in for a secret in the saved history
+const REDACTED: &str = "***";
+
+/// `entry` with any secret it carries replaced by `***`
+///
+/// Covers `/config <provider> <api-key>`, `/config libsql-token <token>`,
+/// `/config header <provider> <name> <value>`, and passwords or `authToken`
+/// parameters in URLs anywhere in the input.
+fn redact_history_entry(entry: &str) -> String {
+    let words: Vec<&str> = entry.split_whitespace().collect();
+    match words.as_slice() {
+        [command, rest @ ..] if command.eq_ignore_ascii_case("/config") => match rest {
+            [header, provider, name, _, ..] if header.eq_ignore_ascii_case("header") => {
+                format!("{} {} {} {} {}", command, header, provider, name, REDACTED)
+            }
+            [key, _, ..] if key.eq_ignore_ascii_case("libsql-token") || !Settings::is_setting(key) => {
+                format!("{} {} {}", command, key, REDACTED)
+            }
+            _ => entry.to_string(),
+        },
+        _ => entry.split(' ').map(redact_url).collect::<Vec<_>>().join(" "),
+    }
+}
+
+/// `word` with the password and `authToken` parameter of a URL masked
+fn redact_url(word: &str) -> String {
+    let Some(scheme_end) = word.find("://").map(|at| at + 3) else {
+        return word.to_string();
+    };
+    let mut redacted = word.to_string();
+
+    let authority_end = word[scheme_end..]
+        .find(['/', '?', '#'])
+        .map_or(word.len(), |at| scheme_end + at);
+    if let Some(at) = word[scheme_end..authority_end].rfind('@') {
+        let userinfo = &word[scheme_end..scheme_end + at];
+        if let Some(colon) = userinfo.find(':') {
+            redacted.replace_range(scheme_end + colon + 1..scheme_end + at, REDACTED);
+        }
+    }
+
+    if let Some(query_start) = redacted.find('?') {
+        let (base, query) = redacted.split_at(query_start + 1);
+        let query: Vec<String> = query
+            .split('&')
+            .map(|param| match param.split_once('=') {
+                Some((name, _)) if name == "authToken" => format!("{}={}", name, REDACTED),
+                _ => param.to_string(),
+            })
+            .collect();
+        redacted = format!("{}{}", base, query.join("&"));
+    }
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::create_shared_state;
+
+    #[derive(Default)]
+    struct RecordingSinks {
+        saved: Option<Vec<String>>,
+        closed: Vec<String>,
+    }
+
+    #[async_trait]
+    impl ShutdownSinks for RecordingSinks {
+        fn save_history(&mut self, history: &[String]) -> Result<()> {
+            self.saved = Some(history.to_vec());
+            Ok(())
+        }
+
+        async fn close_database(&mut self, manager: &DatabaseManager) {
+            manager.close().await;
+            self.closed.push(manager.connection_url().to_string());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_saves_history_and_closes_pool() {
+        let state = create_shared_state();
+        let manager = DatabaseManager::connect_with_options("sqlite::memory:", 1)
+            .await
+            .unwrap();
+        state.write().await.set_database_manager(manager);
+
+        let history = vec!["/connect sqlite::memory:".to_string(), "/quit".to_string()];
+        let mut sinks = RecordingSinks::default();
+        shutdown(&state, &history, &mut sinks).await.unwrap();
+
+        assert_eq!(sinks.saved, Some(history));
+        assert_eq!(sinks.closed, vec!["sqlite::memory:".to_string()]);
+        assert!(!state.read().await.is_connected());
+
+        // Nothing to save or close the second time
+        let mut sinks = RecordingSinks::default();
+        shutdown(&state, &[], &mut sinks).await.unwrap();
+        assert_eq!(sinks.saved, None);
+        assert!(sinks.closed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_terminated_wakes_a_waiting_command() {
+        static SIGNAL: TerminateSignal = TerminateSignal::new();
+
+        let waiter = tokio::spawn(SIGNAL.wait());
+        tokio::task::yield_now().await;
+        SIGNAL.request();
+        let limit = std::time::Duration::from_secs(1);
+        tokio::time::timeout(limit, waiter).await.unwrap().unwrap();
+
+        // Once requested, later waits return at once
+        tokio::time::timeout(limit, SIGNAL.wait()).await.unwrap();
+        assert!(SIGNAL.is_requested());
+        assert!(!terminate_requested());
+    }
+
+    #[test]
+    fn test_save_history_keeps_recent_single_lines() {
+        let path = std::env::temp_dir().join(format!(
+            "schema-forge-history-{}",
+            std::process::id()
+        ));
+        let mut history: Vec<String> = (0..HISTORY_LIMIT + 5).map(|i| format!("q{}", i)).collect();
+        history.push("SELECT 1\nFROM t".to_string());
+
+        save_history_to(&path, &history).unwrap();
+        let saved = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let lines: Vec<&str> = saved.lines().collect();
+        assert_eq!(lines.len(), HISTORY_LIMIT);
+        assert_eq!(lines[0], "q5");
+        assert_eq!(lines[HISTORY_LIMIT - 1], format!("q{}", HISTORY_LIMIT + 4));
+    }
+
+    #[test]
+    fn test_saved_history_masks_secrets() {
+        let path = std::env::temp_dir().join(format!(
+            "schema-forge-history-secrets-{}",
+            std::process::id()
+        ));
+        let history = vec![
+            "/config groq gsk_secret".to_string(),
+            "/config libsql-token eyJhbGciOi".to_string(),
+            "/config header openai X-Api-Key sk-secret".to_string(),
+            "/connect postgresql://admin:hunter2@db:5432/app --as prod".to_string(),
+            "/connect libsql://orders.turso.io?authToken=eyJhbGciOi".to_string(),
+            "/config max-rows 500".to_string(),
+        ];
+
+        save_history_to(&path, &history).unwrap();
+        let saved = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let lines: Vec<&str> = saved.lines().collect();
+        assert_eq!(lines[0], "/config groq ***");
+        assert_eq!(lines[1], "/config libsql-token ***");
+        assert_eq!(lines[2], "/config header openai X-Api-Key ***");
+        assert_eq!(lines[3], "/connect postgresql://admin:***@db:5432/app --as prod");
+        assert_eq!(lines[4], "/connect libsql://orders.turso.io?authToken=***");
+        assert_eq!(lines[5], "/config max-rows 500");
+        assert!(!saved.contains("secret") && !saved.contains("hunter2") && !saved.contains("eyJ"));
+        assert_eq!(redact_history_entry("/config ollama"), "/config ollama");
+    }
+}
@@ -4,9 +4,10 @@
 //! so the interface behaves like an agent shell instead of a scrolling REPL.
 
 use crate::cli::command_menu;
+use crate::cli::shutdown::{self, SessionSinks};
 use crate::cli::commands::{self, Command, CommandType, format_error};
 use crate::config::SharedState;
-use crate::error::Result;
+use crate::error::{Result, SchemaForgeError};
 use crossterm::{
     event::{
         self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
@@ -25,8 +26,17 @@ use ratatui::{
     text::{Line, Span, Text},
     widgets::{Block, Borders, ListState, Paragraph, Wrap},
 };
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::{io, time::Duration};
 
+/// Set while the TUI owns the terminal
+static TUI_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Check whether the TUI is drawing on the terminal, so nothing else should write to it
+pub fn is_active() -> bool {
+    TUI_ACTIVE.load(Ordering::SeqCst)
+}
+
 const HEADER_LOGO: [&str; 5] = [
     "    ╭────╮",
     "  ╭─┤ ◉  ├─╮",
@@ -72,12 +82,14 @@ impl TranscriptEntry {
 #[derive(Default)]
 struct StatusSnapshot {
     connected: bool,
+    connection_label: Option<String>,
     database_backend: Option<String>,
     database_version: Option<String>,
     indexed_tables: usize,
     current_provider: Option<String>,
     current_model: Option<String>,
     configured_providers: usize,
+    provider_names: Vec<String>,
 }
 
 pub struct TuiApp {
@@ -86,6 +98,7 @@ pub struct TuiApp {
     cursor: usize,
     transcript: Vec<TranscriptEntry>,
     command_state: ListState,
+    picker: Option<command_menu::Picker>,
     history: Vec<String>,
     history_index: Option<usize>,
     history_draft: String,
@@ -98,12 +111,17 @@ pub struct TuiApp {
 
 impl TuiApp {
     pub fn new(state: SharedState) -> Self {
+        let quiet = state
+            .try_read()
+            .map(|state_guard| state_guard.quiet())
+            .unwrap_or(false);
         Self {
             state,
             input: String::new(),
             cursor: 0,
-            transcript: Self::welcome_transcript(None),
+            transcript: Self::welcome_transcript(None, quiet),
             command_state: ListState::default().with_selected(Some(0)),
+            picker: None,
             history: Vec::new(),
             history_index: None,
             history_draft: String::new(),
@@ -117,10 +135,14 @@ impl TuiApp {
 
     pub async fn run(&mut self) -> Result<()> {
         let mut terminal = setup_terminal()?;
+        self.history = shutdown::load_history();
         self.refresh_status().await;
 
         let run_result = self.run_loop(&mut terminal).await;
         let restore_result = restore_terminal(&mut terminal);
+        if let Err(error) = shutdown::shutdown(&self.state, &self.history, &mut SessionSinks).await {
+            eprintln!("Could not save input history: {}", error);
+        }
 
         run_result?;
         restore_result?;
@@ -134,7 +156,7 @@ impl TuiApp {
         loop {
             terminal.draw(|frame| self.render(frame))?;
 
-            if self.should_quit {
+            if self.should_quit || shutdown::terminate_requested() {
                 return Ok(());
             }
 
@@ -153,7 +175,11 @@ impl TuiApp {
             if should_submit {
                 self.busy = true;
                 terminal.draw(|frame| self.render(frame))?;
-                self.submit_input().await?;
+                // SIGTERM abandons a running query or LLM call
+                tokio::select! {
+                    submitted = self.submit_input() => submitted?,
+                    () = shutdown::terminated() => return Ok(()),
+                }
                 self.busy = false;
                 self.refresh_status().await;
             }
@@ -161,11 +187,22 @@ impl TuiApp {
     }
 
     fn handle_key_event(&mut self, key: KeyEvent) -> bool {
+        if self.picker.is_some() {
+            return self.handle_picker_key(key);
+        }
+
         match key.code {
             KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 self.should_quit = true;
                 false
             }
+            // End of input, as in a shell
+            KeyCode::Char('d')
+                if key.modifiers.contains(KeyModifiers::CONTROL) && self.input.is_empty() =>
+            {
+                self.should_quit = true;
+                false
+            }
             KeyCode::Enter => {
                 if self.should_show_command_palette() {
                     self.apply_selected_command()
@@ -255,6 +292,53 @@ impl TuiApp {
         }
     }
 
+    fn handle_picker_key(&mut self, key: KeyEvent) -> bool {
+        let Some(picker) = self.picker.as_mut() else {
+            return false;
+        };
+
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.should_quit = true;
+                false
+            }
+            KeyCode::Esc => {
+                self.picker = None;
+                false
+            }
+            KeyCode::Up => {
+                picker.select_previous();
+                false
+            }
+            KeyCode::Down => {
+                picker.select_next();
+                false
+            }
+            KeyCode::Backspace => {
+                picker.pop_filter();
+                false
+            }
+            KeyCode::Char(ch) => {
+                picker.push_filter(ch);
+                false
+            }
+            KeyCode::Enter => match picker.accept() {
+                command_menu::MenuResult::Command(command) => {
+                    self.picker = None;
+                    self.set_input(command);
+                    true
+                }
+                command_menu::MenuResult::Input(partial) => {
+                    self.picker = None;
+                    self.set_input(partial);
+                    false
+                }
+                command_menu::MenuResult::Next | command_menu::MenuResult::Nothing => false,
+            },
+            _ => false,
+        }
+    }
+
     fn handle_mouse_event(&mut self, mouse: MouseEvent) {
         match mouse.kind {
             MouseEventKind::ScrollUp => self.scroll_up(3),
@@ -273,11 +357,21 @@ impl TuiApp {
 
         self.record_history(&submitted);
 
-        if submitted == "/clear" {
-            self.transcript = Self::welcome_transcript(Some(
-                "Session reset. The pinned header stays in place and the conversation starts fresh.",
-            ));
-            self.follow_output = true;
+        if submitted == "/clear" || submitted.starts_with("/clear ") {
+            let cleared = match Command::parse(&submitted) {
+                Ok(command) => commands::handle_command(&command, self.state.clone()).await,
+                Err(error) => Err(error),
+            };
+            match cleared {
+                Ok(message) => {
+                    let quiet = self.state.read().await.quiet();
+                    self.transcript = Self::welcome_transcript(Some(&message), quiet);
+                    self.follow_output = true;
+                }
+                Err(error) => {
+                    self.push_error(&error);
+                }
+            }
             return Ok(());
         }
 
@@ -288,6 +382,7 @@ impl TuiApp {
                 let is_quit = matches!(command.command_type, CommandType::Quit);
 
                 match commands::handle_command(&command, self.state.clone()).await {
+                    Ok(message) if message.is_empty() => {}
                     Ok(message) => {
                         self.push_entry(TranscriptKind::Assistant, "Schema-Forge", message);
                         if is_quit {
@@ -295,12 +390,12 @@ impl TuiApp {
                         }
                     }
                     Err(error) => {
-                        self.push_entry(TranscriptKind::Error, "Error", format_error(&error));
+                        self.push_error(&error);
                     }
                 }
             }
             Err(error) => {
-                self.push_entry(TranscriptKind::Error, "Error", format_error(&error));
+                self.push_error(&error);
             }
         }
 
@@ -316,13 +411,18 @@ impl TuiApp {
             .current_provider
             .as_ref()
             .and_then(|provider| state.get_model(provider));
-        self.status.configured_providers = state.list_providers().len();
+        let mut provider_names = state.list_providers();
+        provider_names.sort();
+        self.status.configured_providers = provider_names.len();
+        self.status.provider_names = provider_names;
 
         if let Some(db_manager) = state.database_manager.as_ref() {
+            self.status.connection_label = db_manager.label().map(str::to_string);
             self.status.database_backend = Some(db_manager.backend().to_string());
             self.status.database_version = db_manager.database_version().await;
             self.status.indexed_tables = db_manager.get_schema_index().await.tables.len();
         } else {
+            self.status.connection_label = None;
             self.status.database_backend = None;
             self.status.database_version = None;
             self.status.indexed_tables = 0;
@@ -331,7 +431,13 @@ impl TuiApp {
 
     fn render(&mut self, frame: &mut Frame) {
         let area = frame.area();
-        let composer_height = if self.should_show_command_palette() { 9 } else { 4 };
+        let composer_height = if self.picker.is_some() {
+            12
+        } else if self.should_show_command_palette() {
+            9
+        } else {
+            4
+        };
         let sections = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
@@ -344,7 +450,14 @@ impl TuiApp {
         self.render_header(frame, sections[0]);
         self.render_body(frame, sections[1]);
 
-        if self.should_show_command_palette() {
+        if let Some(picker) = self.picker.as_mut() {
+            let composer_sections = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(8), Constraint::Min(4)])
+                .split(sections[2]);
+            command_menu::render_picker(frame, composer_sections[0], picker);
+            self.render_input(frame, composer_sections[1]);
+        } else if self.should_show_command_palette() {
             let composer_sections = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([Constraint::Length(5), Constraint::Min(4)])
@@ -498,12 +611,13 @@ impl TuiApp {
     fn render_input(&self, frame: &mut Frame, area: Rect) {
         let prompt = "› ";
         let input_text = if self.input.is_empty() {
+            let placeholder = match self.status.connection_label {
+                Some(ref label) => format!("[{}] Ask about data, run SQL, or type / for commands", label),
+                None => "Ask about data, run SQL, or type / for commands".to_string(),
+            };
             Text::from(Line::from(vec![
                 Span::styled(prompt, Style::default().fg(Color::Green)),
-                Span::styled(
-                    "Ask about data, run SQL, or type / for commands",
-                    Style::default().fg(Color::DarkGray),
-                ),
+                Span::styled(placeholder, Style::default().fg(Color::DarkGray)),
             ]))
         } else {
             Text::from(Line::from(vec![
@@ -584,6 +698,15 @@ impl TuiApp {
         lines
     }
 
+    /// Show an error, tagged with the connection label when there is one
+    fn push_error(&mut self, error: &SchemaForgeError) {
+        let body = match self.status.connection_label {
+            Some(ref label) => format!("[{}] {}", label, format_error(error)),
+            None => format_error(error),
+        };
+        self.push_entry(TranscriptKind::Error, "Error", body);
+    }
+
     fn push_entry(&mut self, kind: TranscriptKind, title: &'static str, body: impl Into<String>) {
         self.transcript.push(TranscriptEntry::new(kind, title, body));
         self.follow_output = true;
@@ -625,6 +748,16 @@ impl TuiApp {
             return false;
         };
 
+        if submit_when_complete {
+            if let Some(picker) =
+                command_menu::Picker::open(command.name, &self.status.provider_names)
+            {
+                self.picker = Some(picker);
+                self.clear_input();
+                return false;
+            }
+        }
+
         self.set_input(command_menu::apply_command(command));
         submit_when_complete && !command.requires_arguments
     }
@@ -856,10 +989,15 @@ impl TuiApp {
 
     fn context_lines(&self) -> Vec<Line<'static>> {
         let database = if self.status.connected {
-            self.status
+            let backend = self
+                .status
                 .database_backend
                 .clone()
-                .unwrap_or_else(|| "connected".to_string())
+                .unwrap_or_else(|| "connected".to_string());
+            match self.status.connection_label {
+                Some(ref label) => format!("{} ({})", label, backend),
+                None => backend,
+            }
         } else {
             "disconnected".to_string()
         };
@@ -924,12 +1062,16 @@ impl TuiApp {
         ]
     }
 
-    fn welcome_transcript(note: Option<&str>) -> Vec<TranscriptEntry> {
-        let mut transcript = vec![TranscriptEntry::new(
-            TranscriptKind::Assistant,
-            "Schema-Forge",
-            "Hello. I work like a database agent: connect a live database, index it immediately, and then ask in plain English or run SQL directly.",
-        )];
+    /// Opening transcript; quiet mode keeps only the session note
+    fn welcome_transcript(note: Option<&str>, quiet: bool) -> Vec<TranscriptEntry> {
+        let mut transcript = Vec::new();
+        if !quiet {
+            transcript.push(TranscriptEntry::new(
+                TranscriptKind::Assistant,
+                "Schema-Forge",
+                "Hello. I work like a database agent: connect a live database, index it immediately, and then ask in plain English or run SQL directly.",
+            ));
+        }
 
         if let Some(note) = note {
             transcript.push(TranscriptEntry::new(
@@ -939,6 +1081,10 @@ impl TuiApp {
             ));
         }
 
+        if quiet {
+            return transcript;
+        }
+
         transcript.push(TranscriptEntry::new(
             TranscriptKind::System,
             "Quick start",
@@ -1111,10 +1257,12 @@ fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
     terminal.clear()?;
+    TUI_ACTIVE.store(true, Ordering::SeqCst);
     Ok(terminal)
 }
 
 fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+    TUI_ACTIVE.store(false, Ordering::SeqCst);
     disable_raw_mode()?;
     execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
     terminal.show_cursor()?;
@@ -1157,6 +1305,18 @@ mod tests {
         assert!(sections.results.is_none());
     }
 
+    #[test]
+    fn test_quiet_mode_skips_welcome() {
+        let state = crate::config::create_shared_state();
+        state.try_write().unwrap().settings.quiet = true;
+        let app = TuiApp::new(state);
+        assert!(app.transcript.is_empty());
+
+        let transcript = TuiApp::welcome_transcript(Some("Cleared."), true);
+        assert_eq!(transcript.len(), 1);
+        assert_eq!(TuiApp::welcome_transcript(None, false).len(), 2);
+    }
+
     #[test]
     fn test_scroll_helpers_update_follow_output() {
         let state = crate::config::create_shared_state();
@@ -3,11 +3,17 @@
 //! This module handles configuration management,
 //! including API key storage and application settings.
 
+pub mod settings;
 pub mod storage;
 
+use crate::cli::commands::Command;
 use crate::database::manager::DatabaseManager;
+use crate::database::results::QueryResult;
 use crate::error::Result;
-use crate::llm::provider::Message;
+use crate::llm::breaker::CircuitBreaker;
+use crate::llm::provider::{LLMProvider, Message};
+use crate::llm::provider_registry;
+use settings::Settings;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -22,28 +28,117 @@ pub struct AppState {
     pub models: HashMap<String, String>,
     /// Current selected provider
     pub current_provider: Option<String>,
+    /// Extra HTTP headers sent with every request, per provider
+    pub headers: HashMap<String, HashMap<String, String>>,
     /// In-memory multi-turn conversation history for the active session
     pub conversation_history: Vec<Message>,
+    /// Behaviour settings
+    pub settings: Settings,
+    /// Startup-flag overrides for this session, never saved
+    pub overrides: SessionOverrides,
+    /// Most recent query result, kept for `/show` and `/copy`
+    pub last_result: Option<QueryResult>,
+    /// Most recently executed SQL, kept for `/copy sql`
+    pub last_sql: Option<String>,
+    /// Latest result of each `/diff-query`, keyed by normalized SQL
+    pub query_snapshots: HashMap<String, QueryResult>,
+    /// Generated SQL that changes data, waiting for `/confirm`
+    pub pending_sql: Option<String>,
+    /// Most recent command that failed, kept for `/retry`
+    pub last_failed_command: Option<Command>,
+    /// Provider clients already built, so queries reuse their HTTP connections
+    providers: HashMap<ProviderCacheKey, Arc<dyn LLMProvider>>,
+    /// Pauses a provider after repeated failures this session
+    pub breaker: CircuitBreaker,
 }
 
+/// Values set by startup flags for the running session only
+///
+/// Kept apart from `Settings` and the provider maps so saving after a
+/// `/config` change never writes them to the config file. A `/config` or
+/// `/provider` change to the same value replaces the override.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionOverrides {
+    /// `--provider`
+    pub provider: Option<String>,
+    /// `--model`, keyed by the provider it was given for
+    pub models: HashMap<String, String>,
+    /// `--quiet`
+    pub quiet: Option<bool>,
+    /// `--max-retries`
+    pub retries: Option<u32>,
+}
+
+impl SessionOverrides {
+    /// Overridden settings as `(key, value)` pairs in their `/config` form
+    pub fn settings(&self) -> Vec<(&'static str, String)> {
+        let mut settings = Vec::new();
+        if let Some(quiet) = self.quiet {
+            settings.push(("quiet", if quiet { "on" } else { "off" }.to_string()));
+        }
+        if let Some(retries) = self.retries {
+            settings.push(("retries", retries.to_string()));
+        }
+        settings
+    }
+
+    /// Drop the override for a `/config` key, if there is one
+    fn clear_setting(&mut self, key: &str) {
+        if key.eq_ignore_ascii_case("quiet") {
+            self.quiet = None;
+        } else if key.eq_ignore_ascii_case("retries") {
+            self.retries = None;
+        }
+    }
+}
+
+/// What a cached provider client was built for: provider, model and base URL
+type ProviderCacheKey = (String, Option<String>, Option<String>);
+
 impl AppState {
     /// Create a new application state, loading from disk if available
     pub fn new() -> Self {
-        // Try to load from disk, fall back to empty state
-        match storage::Config::load() {
-            Ok(config) => Self {
-                database_manager: None,
-                api_keys: config.api_keys,
-                models: config.models,
-                current_provider: config.current_provider,
-                conversation_history: Vec::new(),
-            },
+        // Try to load from disk, fall back to empty state. A corrupt config
+        // is set aside with a warning rather than silently replaced.
+        match storage::Config::load_or_recover() {
+            Ok((config, warning)) => {
+                if let Some(warning) = warning {
+                    eprintln!("{}", warning);
+                }
+                Self {
+                    database_manager: None,
+                    api_keys: config.api_keys,
+                    models: config.models,
+                    current_provider: config.current_provider,
+                    headers: config.headers,
+                    conversation_history: Vec::new(),
+                    settings: config.settings,
+                    overrides: SessionOverrides::default(),
+                    last_result: None,
+                    last_sql: None,
+                    query_snapshots: HashMap::new(),
+                    pending_sql: None,
+                    last_failed_command: None,
+                    providers: HashMap::new(),
+                    breaker: CircuitBreaker::default(),
+                }
+            }
             Err(_) => Self {
                 database_manager: None,
                 api_keys: HashMap::new(),
                 models: storage::Config::default_models(),
                 current_provider: None,
+                headers: HashMap::new(),
                 conversation_history: Vec::new(),
+                settings: Settings::default(),
+                overrides: SessionOverrides::default(),
+                last_result: None,
+                last_sql: None,
+                query_snapshots: HashMap::new(),
+                pending_sql: None,
+                last_failed_command: None,
+                providers: HashMap::new(),
+                breaker: CircuitBreaker::default(),
             },
         }
     }
@@ -51,14 +146,21 @@ impl AppState {
     /// Set the database manager
     pub fn set_database_manager(&mut self, manager: DatabaseManager) {
         self.database_manager = Some(manager);
+        self.apply_settings_to_manager();
+        self.last_result = None;
+        self.last_sql = None;
+        self.query_snapshots.clear();
+        self.pending_sql = None;
         self.clear_conversation_history();
     }
 
     /// Store an API key for a provider and save to disk
     pub fn set_api_key(&mut self, provider: String, key: String) {
         self.api_keys.insert(provider.clone(), key);
+        self.clear_provider_cache();
         // Always make the newly configured provider the current one
         self.current_provider = Some(provider.clone());
+        self.overrides.provider = None;
         // Save to disk
         let _ = self.save();
     }
@@ -70,19 +172,35 @@ impl AppState {
 
     /// Set model for a provider and save to disk
     pub fn set_model(&mut self, provider: String, model: String) {
+        self.overrides.models.remove(&provider);
         self.models.insert(provider, model);
+        self.clear_provider_cache();
         // Save to disk
         let _ = self.save();
     }
 
-    /// Get model for a provider
+    /// Get model for a provider, after any `--model` override
     pub fn get_model(&self, provider: &str) -> Option<String> {
-        self.models.get(provider).cloned()
+        self.overrides
+            .models
+            .get(provider)
+            .or_else(|| self.models.get(provider))
+            .cloned()
     }
 
     /// Remove model for a provider (revert to default) and save to disk
     pub fn remove_model(&mut self, provider: &str) {
+        self.overrides.models.remove(provider);
         self.models.remove(provider);
+        self.clear_provider_cache();
+        // Save to disk
+        let _ = self.save();
+    }
+
+    /// Send `name: value` with every request to `provider` and save to disk
+    pub fn set_header(&mut self, provider: String, name: String, value: String) {
+        self.headers.entry(provider).or_default().insert(name, value);
+        self.clear_provider_cache();
         // Save to disk
         let _ = self.save();
     }
@@ -90,13 +208,101 @@ impl AppState {
     /// Set the current provider and save to disk
     pub fn set_current_provider(&mut self, provider: String) {
         self.current_provider = Some(provider);
+        self.overrides.provider = None;
         // Save to disk
         let _ = self.save();
     }
 
-    /// Get the current provider
+    /// Get the current provider, after any `--provider` override
     pub fn get_current_provider(&self) -> Option<&String> {
-        self.current_provider.as_ref()
+        self.overrides.provider.as_ref().or(self.current_provider.as_ref())
+    }
+
+    /// Client for `provider` with `model`, built on first use and then reused
+    ///
+    /// Reusing the client keeps its HTTP connection pool warm across queries.
+    pub fn llm_provider(
+        &mut self,
+        provider: &str,
+        api_key: &str,
+        model: Option<String>,
+    ) -> Result<Arc<dyn LLMProvider>> {
+        let key = (
+            provider.to_string(),
+            model.clone(),
+            provider_registry::base_url_override(provider),
+        );
+        if let Some(client) = self.providers.get(&key) {
+            return Ok(client.clone());
+        }
+
+        let mut client = provider_registry::create_provider(provider, api_key, model)?;
+        client.set_custom_headers(self.headers.get(provider).cloned().unwrap_or_default());
+        client.set_max_retries(self.retries());
+        let client: Arc<dyn LLMProvider> = Arc::from(client);
+        self.providers.insert(key, client.clone());
+        Ok(client)
+    }
+
+    /// Drop cached provider clients so the next query builds fresh ones
+    pub fn clear_provider_cache(&mut self) {
+        self.providers.clear();
+    }
+
+    /// Whether informational notes are hidden, after any `--quiet` override
+    pub fn quiet(&self) -> bool {
+        self.overrides.quiet.unwrap_or(self.settings.quiet)
+    }
+
+    /// Retry limit for LLM requests, after any `--max-retries` override
+    pub fn retries(&self) -> u32 {
+        self.overrides.retries.unwrap_or(self.settings.retries)
+    }
+
+    /// Apply a `/config <setting> <value>` change and save to disk
+    pub fn set_setting(&mut self, key: &str, value: &str) -> Result<String> {
+        let message = self.settings.apply(key, value)?;
+        self.overrides.clear_setting(key);
+        self.apply_settings_to_manager();
+        // Provider clients are built with the retry limit
+        if key.eq_ignore_ascii_case("retries") {
+            self.clear_provider_cache();
+        }
+        // Save to disk
+        let _ = self.save();
+        Ok(message)
+    }
+
+    /// Restore every setting to its default and save to disk
+    ///
+    /// API keys, models and the current provider are kept.
+    pub fn reset_settings(&mut self) {
+        self.settings = Settings::default();
+        self.overrides = SessionOverrides::default();
+        self.apply_settings_to_manager();
+        self.clear_provider_cache();
+        // Save to disk
+        let _ = self.save();
+    }
+
+    /// Push the settings the database manager uses down to it
+    fn apply_settings_to_manager(&mut self) {
+        if let Some(manager) = self.database_manager.as_mut() {
+            manager.set_binary_preview_bytes(self.settings.binary_preview_bytes);
+            manager.set_sample_json(self.settings.sample_json);
+            manager.set_include_system_tables(self.settings.include_system_tables);
+            manager.set_auto_reindex(self.settings.auto_reindex);
+            manager.set_max_rows(self.settings.max_rows);
+        }
+    }
+
+    /// Forget conversation, results and pending SQL without disconnecting
+    pub fn reset_session(&mut self) {
+        self.clear_conversation_history();
+        self.last_result = None;
+        self.last_sql = None;
+        self.query_snapshots.clear();
+        self.pending_sql = None;
     }
 
     /// Check if database is connected
@@ -128,14 +334,80 @@ impl AppState {
         self.conversation_history.clear();
     }
 
-    /// Save configuration to disk
-    fn save(&self) -> Result<()> {
-        let config = storage::Config {
+    /// Move a provider's key, model and current selection to `new` and save
+    ///
+    /// Returns whether `old` was configured.
+    pub fn rename_provider(&mut self, old: &str, new: &str) -> bool {
+        let mut config = self.to_config();
+        if !config.rename_provider(old, new) {
+            return false;
+        }
+
+        self.clear_provider_cache();
+        self.api_keys = config.api_keys;
+        self.models = config.models;
+        self.headers = config.headers;
+        self.current_provider = config.current_provider;
+        if self.overrides.provider.as_deref() == Some(old) {
+            self.overrides.provider = Some(new.to_string());
+        }
+        if let Some(model) = self.overrides.models.remove(old) {
+            self.overrides.models.insert(new.to_string(), model);
+        }
+        // Save to disk
+        let _ = self.save();
+        true
+    }
+
+    /// Configured providers that are no longer supported
+    pub fn unknown_providers(&self) -> Vec<String> {
+        self.to_config().unknown_providers()
+    }
+
+    /// Remove every unsupported provider from the config and save to disk
+    ///
+    /// Returns the names that were removed.
+    pub fn remove_unknown_providers(&mut self) -> Vec<String> {
+        let mut config = self.to_config();
+        let unknown = config.unknown_providers();
+        if unknown.is_empty() {
+            return unknown;
+        }
+
+        config.remove_providers(&unknown);
+        if self
+            .overrides
+            .provider
+            .as_ref()
+            .is_some_and(|provider| unknown.contains(provider))
+        {
+            self.overrides.provider = None;
+        }
+        self.overrides.models.retain(|provider, _| !unknown.contains(provider));
+        self.clear_provider_cache();
+        self.api_keys = config.api_keys;
+        self.models = config.models;
+        self.headers = config.headers;
+        self.current_provider = config.current_provider;
+        // Save to disk
+        let _ = self.save();
+        unknown
+    }
+
+    /// Snapshot the persisted part of the state
+    fn to_config(&self) -> storage::Config {
+        storage::Config {
             api_keys: self.api_keys.clone(),
             models: self.models.clone(),
             current_provider: self.current_provider.clone(),
-        };
-        config.save()
+            settings: self.settings.clone(),
+            headers: self.headers.clone(),
+        }
+    }
+
+    /// Save configuration to disk
+    fn save(&self) -> Result<()> {
+        self.to_config().save()
     }
 }
 
@@ -152,3 +424,54 @@ pub type SharedState = Arc<RwLock<AppState>>;
 pub fn create_shared_state() -> SharedState {
     Arc::new(RwLock::new(AppState::new()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_llm_provider_is_reused() {
+        let mut state = AppState::new();
+        let first = state.llm_provider("openai", "test-key", None).unwrap();
+        let second = state.llm_provider("openai", "test-key", None).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        let other_model = state
+            .llm_provider("openai", "test-key", Some("gpt-4".to_string()))
+            .unwrap();
+        assert!(!Arc::ptr_eq(&first, &other_model));
+
+        state.clear_provider_cache();
+        let rebuilt = state.llm_provider("openai", "test-key", None).unwrap();
+        assert!(!Arc::ptr_eq(&first, &rebuilt));
+        assert!(state.llm_provider("bard", "test-key", None).is_err());
+    }
+
+    #[test]
+    fn test_session_overrides_are_not_saved() {
+        let mut state = AppState::new();
+        let saved = state.settings.retries;
+        state.overrides.retries = Some(saved + 1);
+        assert_eq!(state.retries(), saved + 1);
+        assert_eq!(state.to_config().settings.retries, saved);
+
+        state.overrides.clear_setting("RETRIES");
+        assert_eq!(state.retries(), saved);
+
+        state.overrides.provider = Some("groq".to_string());
+        state
+            .overrides
+            .models
+            .insert("groq".to_string(), "llama-3.3-70b-versatile".to_string());
+        assert_eq!(state.get_current_provider().map(String::as_str), Some("groq"));
+        assert_eq!(state.get_model("groq").as_deref(), Some("llama-3.3-70b-versatile"));
+        let config = state.to_config();
+        assert_eq!(config.current_provider, state.current_provider);
+        assert_eq!(config.models.get("groq"), state.models.get("groq"));
+
+        state.settings.quiet = false;
+        state.overrides.quiet = Some(true);
+        assert!(state.quiet());
+        assert!(!state.to_config().settings.quiet);
+    }
+}
@@ -0,0 +1,810 @@
+//! Session Settings
+//!
+//! This module defines the behaviour toggles that users change with
+//! `/config <setting> <value>`. Settings are persisted next to API keys.
+
+use crate::database::results::NULL_CELL;
+use crate::database::schema::ColumnOrder;
+use crate::error::{Result, SchemaForgeError};
+use crate::llm::client::DEFAULT_MAX_RETRIES;
+use serde::{Deserialize, Serialize};
+
+/// Default cap on schema context sent to the LLM (in characters)
+pub const DEFAULT_CONTEXT_CAP: usize = 48_000;
+
+/// Default maximum width of a result cell (in characters)
+pub const DEFAULT_CELL_WIDTH: usize = 64;
+
+/// Default number of bytes shown for binary values
+pub const DEFAULT_BINARY_PREVIEW_BYTES: usize = 32;
+
+/// Default estimated row count above which `SELECT *` needs confirmation
+pub const DEFAULT_BIG_TABLE_ROWS: usize = 100_000;
+
+/// Default number of rows fetched for a query before the rest are dropped
+pub const DEFAULT_MAX_ROWS: usize = 10_000;
+
+/// How the schema is rendered into LLM context
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContextMode {
+    /// Full schema; warn instead of sending when it exceeds the cap
+    Full,
+    /// Compact table/column summary
+    Summary,
+    /// Full schema trimmed to whole tables that fit within the cap
+    Budget,
+    /// Table names and foreign key relationships only, for join questions
+    Relationships,
+}
+
+impl std::fmt::Display for ContextMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContextMode::Full => write!(f, "full"),
+            ContextMode::Summary => write!(f, "summary"),
+            ContextMode::Budget => write!(f, "budget"),
+            ContextMode::Relationships => write!(f, "relationships"),
+        }
+    }
+}
+
+/// What a natural language question does while no database is connected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NlOfflineMode {
+    /// Refuse with a "not connected" error
+    Strict,
+    /// Send it to the LLM as a general question, without schema
+    Allow,
+}
+
+impl std::fmt::Display for NlOfflineMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NlOfflineMode::Strict => write!(f, "strict"),
+            NlOfflineMode::Allow => write!(f, "allow"),
+        }
+    }
+}
+
+/// User-configurable settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Ask the LLM for a short rationale after generating SQL
+    pub explain_sql: bool,
+    /// How the schema is rendered into LLM context
+    pub context_mode: ContextMode,
+    /// Maximum schema context size in characters
+    pub context_cap: usize,
+    /// Result cells longer than this are truncated with "…"
+    pub cell_width: usize,
+    /// Most tables to include in LLM context (unlimited when `None`)
+    pub max_context_tables: Option<usize>,
+    /// Bytes of a binary value shown as hex before it is cut off
+    pub binary_preview_bytes: usize,
+    /// Sample JSON columns while indexing and send their key names to the LLM
+    pub sample_json: bool,
+    /// Reindex as soon as executed SQL changes the schema
+    pub auto_reindex: bool,
+    /// Unbounded `SELECT *` on a table estimated above this many rows needs `/confirm`
+    pub big_table_rows: usize,
+    /// Answer trivial questions ("list tables", "count users") without the LLM
+    pub nl_fastpath: bool,
+    /// Break echoed SQL onto one line per clause
+    pub pretty_sql: bool,
+    /// Server-side statement timeout for PostgreSQL in milliseconds (off when `None`)
+    pub statement_timeout_ms: Option<u64>,
+    /// Auth token for `libsql://` URLs that carry no `authToken` parameter
+    pub libsql_token: Option<String>,
+    /// What natural language questions do while disconnected
+    pub nl_offline: NlOfflineMode,
+    /// Ask the LLM how to fix direct SQL the database rejects
+    pub explain_errors: bool,
+    /// Connect to `$DATABASE_URL` at startup when `--db` is not given
+    pub auto_connect: bool,
+    /// Leave out the welcome and informational notes, keeping only results and errors
+    pub quiet: bool,
+    /// Order of columns in `/describe` output
+    pub column_order: ColumnOrder,
+    /// Check generated SQL for tables and columns missing from the index
+    pub validate_sql: bool,
+    /// Text shown for NULL cells in tables, markdown and CSV
+    pub null_display: String,
+    /// Rows fetched for a read-only query before the rest are dropped
+    pub max_rows: Option<usize>,
+    /// Keep catalog tables (pg_*, sqlite_*, information_schema, ...) when indexing
+    pub include_system_tables: bool,
+    /// Times a failed LLM request is retried (0 reports the first failure)
+    pub retries: u32,
+    /// Group digits of numeric cells in result tables, e.g. `1,234,567`
+    pub number_grouping: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            explain_sql: false,
+            context_mode: ContextMode::Full,
+            context_cap: DEFAULT_CONTEXT_CAP,
+            cell_width: DEFAULT_CELL_WIDTH,
+            max_context_tables: None,
+            binary_preview_bytes: DEFAULT_BINARY_PREVIEW_BYTES,
+            sample_json: false,
+            auto_reindex: false,
+            big_table_rows: DEFAULT_BIG_TABLE_ROWS,
+            nl_fastpath: true,
+            pretty_sql: true,
+            statement_timeout_ms: None,
+            libsql_token: None,
+            nl_offline: NlOfflineMode::Strict,
+            explain_errors: false,
+            auto_connect: true,
+            quiet: false,
+            column_order: ColumnOrder::Ordinal,
+            validate_sql: false,
+            null_display: NULL_CELL.to_string(),
+            max_rows: Some(DEFAULT_MAX_ROWS),
+            include_system_tables: false,
+            retries: DEFAULT_MAX_RETRIES,
+            number_grouping: false,
+        }
+    }
+}
+
+impl Settings {
+    /// Setting names accepted by `/config <setting> <value>`
+    pub const KEYS: &'static [&'static str] = &[
+        "explain-sql",
+        "context-mode",
+        "context-cap",
+        "cell-width",
+        "max-context-tables",
+        "binary-preview-bytes",
+        "sample-json",
+        "auto-reindex",
+        "big-table-rows",
+        "nl-fastpath",
+        "pretty-sql",
+        "statement-timeout",
+        "libsql-token",
+        "nl-offline",
+        "explain-errors",
+        "auto-connect",
+        "quiet",
+        "column-order",
+        "validate-sql",
+        "null-display",
+        "max-rows",
+        "include-system-tables",
+        "retries",
+        "number-grouping",
+    ];
+
+    /// Current value of a setting in its `/config` form
+    pub fn value(&self, key: &str) -> Option<String> {
+        let toggle = |enabled: bool| if enabled { "on" } else { "off" }.to_string();
+        Some(match key.to_lowercase().as_str() {
+            "explain-sql" => toggle(self.explain_sql),
+            "context-mode" => self.context_mode.to_string(),
+            "context-cap" => self.context_cap.to_string(),
+            "cell-width" => self.cell_width.to_string(),
+            "max-context-tables" => self
+                .max_context_tables
+                .map_or_else(|| "off".to_string(), |limit| limit.to_string()),
+            "binary-preview-bytes" => self.binary_preview_bytes.to_string(),
+            "sample-json" => toggle(self.sample_json),
+            "auto-reindex" => toggle(self.auto_reindex),
+            "big-table-rows" => self.big_table_rows.to_string(),
+            "nl-fastpath" => toggle(self.nl_fastpath),
+            "pretty-sql" => toggle(self.pretty_sql),
+            "statement-timeout" => self
+                .statement_timeout_ms
+                .map_or_else(|| "off".to_string(), |ms| ms.to_string()),
+            // Never echo the token itself
+            "libsql-token" => if self.libsql_token.is_some() { "set" } else { "off" }.to_string(),
+            "nl-offline" => self.nl_offline.to_string(),
+            "explain-errors" => toggle(self.explain_errors),
+            "auto-connect" => toggle(self.auto_connect),
+            "quiet" => toggle(self.quiet),
+            "column-order" => self.column_order.to_string(),
+            "validate-sql" => toggle(self.validate_sql),
+            "null-display" if self.null_display.is_empty() => "\"\"".to_string(),
+            "null-display" => self.null_display.clone(),
+            "max-rows" => self
+                .max_rows
+                .map_or_else(|| "off".to_string(), |limit| limit.to_string()),
+            "include-system-tables" => toggle(self.include_system_tables),
+            "retries" => self.retries.to_string(),
+            "number-grouping" => toggle(self.number_grouping),
+            _ => return None,
+        })
+    }
+
+    /// Every setting with its value and where it comes from
+    ///
+    /// The source is "default" when the value matches the built-in default
+    /// and "config" when it was changed with `/config` and saved.
+    pub fn describe(&self) -> Vec<(&'static str, String, &'static str)> {
+        let defaults = Settings::default();
+        Self::KEYS
+            .iter()
+            .map(|key| {
+                let value = self.value(key).unwrap_or_default();
+                let source = if defaults.value(key).as_ref() == Some(&value) {
+                    "default"
+                } else {
+                    "config"
+                };
+                (*key, value, source)
+            })
+            .collect()
+    }
+
+    /// Check whether a `/config` key names a setting rather than a provider
+    pub fn is_setting(key: &str) -> bool {
+        Self::KEYS.iter().any(|k| k.eq_ignore_ascii_case(key))
+    }
+
+    /// Apply a setting from its `/config` form and describe the change
+    pub fn apply(&mut self, key: &str, value: &str) -> Result<String> {
+        match key.to_lowercase().as_str() {
+            "explain-sql" => {
+                self.explain_sql = parse_toggle(key, value)?;
+                Ok(format!(
+                    "SQL explanations {}",
+                    if self.explain_sql { "enabled" } else { "disabled" }
+                ))
+            }
+            "context-mode" => {
+                self.context_mode = match value.trim().to_lowercase().as_str() {
+                    "full" => ContextMode::Full,
+                    "summary" => ContextMode::Summary,
+                    "budget" => ContextMode::Budget,
+                    "relationships" => ContextMode::Relationships,
+                    _ => {
+                        return Err(SchemaForgeError::InvalidConfig {
+                            key: key.to_string(),
+                            value: value.to_string(),
+                            expected: "full, summary, budget, or relationships".to_string(),
+                        })
+                    }
+                };
+                Ok(format!("Schema context mode set to {}", self.context_mode))
+            }
+            "context-cap" => {
+                self.context_cap = parse_positive(key, value)?;
+                Ok(format!(
+                    "Schema context cap set to {} characters",
+                    self.context_cap
+                ))
+            }
+            "cell-width" => {
+                self.cell_width = parse_positive(key, value)?;
+                Ok(format!(
+                    "Result cells truncated after {} characters (use /show <row> <col> for full values)",
+                    self.cell_width
+                ))
+            }
+            "max-context-tables" => {
+                self.max_context_tables = match value.trim().to_lowercase().as_str() {
+                    "off" | "none" | "0" => None,
+                    _ => Some(parse_positive(key, value)?),
+                };
+                Ok(match self.max_context_tables {
+                    Some(limit) => format!("LLM context limited to {} tables", limit),
+                    None => "LLM context table limit removed".to_string(),
+                })
+            }
+            "binary-preview-bytes" => {
+                self.binary_preview_bytes = parse_positive(key, value)?;
+                Ok(format!(
+                    "Binary values show their first {} bytes as hex",
+                    self.binary_preview_bytes
+                ))
+            }
+            "sample-json" => {
+                self.sample_json = parse_toggle(key, value)?;
+                Ok(format!(
+                    "JSON column sampling {}{}",
+                    if self.sample_json { "enabled" } else { "disabled" },
+                    if self.sample_json { " (run /index to sample now)" } else { "" }
+                ))
+            }
+            "auto-reindex" => {
+                self.auto_reindex = parse_toggle(key, value)?;
+                Ok(if self.auto_reindex {
+                    "Schema reindexed as soon as SQL changes it".to_string()
+                } else {
+                    "Schema reindexed on next use after SQL changes it".to_string()
+                })
+            }
+            "big-table-rows" => {
+                self.big_table_rows = parse_positive(key, value)?;
+                Ok(format!(
+                    "SELECT * without a limit needs /confirm on tables over {} estimated rows",
+                    self.big_table_rows
+                ))
+            }
+            "nl-fastpath" => {
+                self.nl_fastpath = parse_toggle(key, value)?;
+                Ok(if self.nl_fastpath {
+                    "Simple questions like \"count users\" are answered without the LLM".to_string()
+                } else {
+                    "Every question is sent to the LLM".to_string()
+                })
+            }
+            "pretty-sql" => {
+                self.pretty_sql = parse_toggle(key, value)?;
+                Ok(format!(
+                    "Echoed SQL {}",
+                    if self.pretty_sql { "formatted one clause per line" } else { "shown as generated" }
+                ))
+            }
+            "statement-timeout" => {
+                self.statement_timeout_ms = match value.trim().to_lowercase().as_str() {
+                    "off" | "none" | "0" => None,
+                    _ => Some(parse_positive(key, value)? as u64),
+                };
+                Ok(match self.statement_timeout_ms {
+                    Some(ms) => format!("PostgreSQL statement_timeout set to {} ms", ms),
+                    None => "PostgreSQL statement_timeout removed".to_string(),
+                })
+            }
+            "libsql-token" => {
+                let token = value.trim();
+                self.libsql_token = match token.to_lowercase().as_str() {
+                    "" | "off" | "none" => None,
+                    _ => Some(token.to_string()),
+                };
+                Ok(if self.libsql_token.is_some() {
+                    "libSQL auth token saved; used by /connect libsql:// URLs without authToken".to_string()
+                } else {
+                    "libSQL auth token removed".to_string()
+                })
+            }
+            "nl-offline" => {
+                self.nl_offline = match value.trim().to_lowercase().as_str() {
+                    "strict" => NlOfflineMode::Strict,
+                    "allow" => NlOfflineMode::Allow,
+                    _ => {
+                        return Err(SchemaForgeError::InvalidConfig {
+                            key: key.to_string(),
+                            value: value.to_string(),
+                            expected: "strict or allow".to_string(),
+                        })
+                    }
+                };
+                Ok(match self.nl_offline {
+                    NlOfflineMode::Strict => "Questions need a database connection".to_string(),
+                    NlOfflineMode::Allow => {
+                        "Questions asked while disconnected go to the LLM without schema".to_string()
+                    }
+                })
+            }
+            "explain-errors" => {
+                self.explain_errors = parse_toggle(key, value)?;
+                Ok(if self.explain_errors {
+                    "Failed SQL is sent to the LLM for a suggested fix".to_string()
+                } else {
+                    "Failed SQL shows only the database error".to_string()
+                })
+            }
+            "auto-connect" => {
+                self.auto_connect = parse_toggle(key, value)?;
+                Ok(if self.auto_connect {
+                    "DATABASE_URL is connected at startup when --db is not given".to_string()
+                } else {
+                    "DATABASE_URL is ignored at startup".to_string()
+                })
+            }
+            "quiet" => {
+                self.quiet = parse_toggle(key, value)?;
+                Ok(if self.quiet {
+                    "Welcome and informational notes are hidden".to_string()
+                } else {
+                    "Welcome and informational notes are shown".to_string()
+                })
+            }
+            "column-order" => {
+                self.column_order = match value.trim().to_lowercase().as_str() {
+                    "ordinal" => ColumnOrder::Ordinal,
+                    "alpha" => ColumnOrder::Alpha,
+                    _ => {
+                        return Err(SchemaForgeError::InvalidConfig {
+                            key: key.to_string(),
+                            value: value.to_string(),
+                            expected: "alpha or ordinal".to_string(),
+                        })
+                    }
+                };
+                Ok(match self.column_order {
+                    ColumnOrder::Ordinal => "/describe lists columns in definition order".to_string(),
+                    ColumnOrder::Alpha => "/describe lists columns alphabetically".to_string(),
+                })
+            }
+            "validate-sql" => {
+                self.validate_sql = parse_toggle(key, value)?;
+                Ok(if self.validate_sql {
+                    "Generated SQL naming unknown tables or columns waits for /confirm".to_string()
+                } else {
+                    "Generated SQL is not checked against the schema index".to_string()
+                })
+            }
+            "null-display" => {
+                // "" (or '') shows NULL cells as empty
+                self.null_display = match value.trim() {
+                    "\"\"" | "''" => String::new(),
+                    display => display.to_string(),
+                };
+                Ok(if self.null_display.is_empty() {
+                    "NULL cells are shown empty".to_string()
+                } else {
+                    format!("NULL cells are shown as {}", self.null_display)
+                })
+            }
+            "max-rows" => {
+                self.max_rows = match value.trim().to_lowercase().as_str() {
+                    "off" | "none" | "0" => None,
+                    _ => Some(parse_positive(key, value)?),
+                };
+                Ok(match self.max_rows {
+                    Some(limit) => format!("Queries stop fetching after {} rows", limit),
+                    None => "Query row cap removed".to_string(),
+                })
+            }
+            "include-system-tables" => {
+                self.include_system_tables = parse_toggle(key, value)?;
+                Ok(format!(
+                    "System tables are {} when indexing (run /index to apply)",
+                    if self.include_system_tables { "kept" } else { "skipped" }
+                ))
+            }
+            "number-grouping" => {
+                self.number_grouping = parse_toggle(key, value)?;
+                Ok(format!(
+                    "Numbers in result tables are {} (exports stay raw)",
+                    if self.number_grouping { "grouped with thousands separators" } else { "shown as returned" }
+                ))
+            }
+            "retries" => {
+                self.retries = value.trim().parse::<u32>().map_err(|_| {
+                    SchemaForgeError::InvalidConfig {
+                        key: key.to_string(),
+                        value: value.to_string(),
+                        expected: "0 or a positive integer".to_string(),
+                    }
+                })?;
+                Ok(match self.retries {
+                    0 => "Failed LLM requests are reported immediately, without retries".to_string(),
+                    n => format!("Failed LLM requests are retried up to {} times", n),
+                })
+            }
+            _ => Err(SchemaForgeError::InvalidInput(format!(
+                "Unknown setting: '{}'. Supported: {}",
+                key,
+                Self::KEYS.join(", ")
+            ))),
+        }
+    }
+}
+
+/// Parse an on/off style value
+fn parse_toggle(key: &str, value: &str) -> Result<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "on" | "true" | "yes" | "1" => Ok(true),
+        "off" | "false" | "no" | "0" => Ok(false),
+        _ => Err(SchemaForgeError::InvalidConfig {
+            key: key.to_string(),
+            value: value.to_string(),
+            expected: "on or off".to_string(),
+        }),
+    }
+}
+
+/// Parse a strictly positive integer value
+fn parse_positive(key: &str, value: &str) -> Result<usize> {
+    value
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .filter(|n| *n > 0)
+        .ok_or_else(|| SchemaForgeError::InvalidConfig {
+            key: key.to_string(),
+            value: value.to_string(),
+            expected: "a positive integer".to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_default() {
+        let settings = Settings::default();
+        assert!(!settings.explain_sql);
+    }
+
+    #[test]
+    fn test_describe_reports_value_and_source() {
+        let mut settings = Settings::default();
+        assert!(settings
+            .describe()
+            .iter()
+            .all(|(_, _, source)| *source == "default"));
+        assert_eq!(settings.describe().len(), Settings::KEYS.len());
+
+        settings.apply("cell-width", "20").unwrap();
+        settings.apply("max-context-tables", "off").unwrap();
+        let described = settings.describe();
+        let entry = |key: &str| described.iter().find(|(k, _, _)| *k == key).unwrap().clone();
+        assert_eq!(entry("cell-width"), ("cell-width", "20".to_string(), "config"));
+        assert_eq!(
+            entry("max-context-tables"),
+            ("max-context-tables", "off".to_string(), "default")
+        );
+        assert_eq!(entry("explain-sql"), ("explain-sql", "off".to_string(), "default"));
+    }
+
+    #[test]
+    fn test_apply_toggle() {
+        let mut settings = Settings::default();
+        settings.apply("explain-sql", "on").unwrap();
+        assert!(settings.explain_sql);
+
+        settings.apply("explain-sql", "off").unwrap();
+        assert!(!settings.explain_sql);
+
+        assert!(settings.apply("explain-sql", "maybe").is_err());
+        assert!(settings.apply("unknown", "on").is_err());
+    }
+
+    #[test]
+    fn test_apply_context_settings() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.context_mode, ContextMode::Full);
+        assert_eq!(settings.context_cap, DEFAULT_CONTEXT_CAP);
+
+        settings.apply("context-mode", "summary").unwrap();
+        assert_eq!(settings.context_mode, ContextMode::Summary);
+        settings.apply("context-mode", "Relationships").unwrap();
+        assert_eq!(settings.context_mode, ContextMode::Relationships);
+        assert_eq!(settings.value("context-mode").as_deref(), Some("relationships"));
+        settings.apply("context-cap", "1000").unwrap();
+        assert_eq!(settings.context_cap, 1000);
+
+        assert!(settings.apply("context-mode", "tiny").is_err());
+        assert!(settings.apply("context-cap", "0").is_err());
+    }
+
+    #[test]
+    fn test_apply_cell_width() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.cell_width, DEFAULT_CELL_WIDTH);
+
+        settings.apply("cell-width", "20").unwrap();
+        assert_eq!(settings.cell_width, 20);
+        assert!(settings.apply("cell-width", "wide").is_err());
+    }
+
+    #[test]
+    fn test_apply_max_context_tables() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.max_context_tables, None);
+
+        settings.apply("max-context-tables", "25").unwrap();
+        assert_eq!(settings.max_context_tables, Some(25));
+        settings.apply("max-context-tables", "off").unwrap();
+        assert_eq!(settings.max_context_tables, None);
+        assert!(settings.apply("max-context-tables", "-1").is_err());
+    }
+
+    #[test]
+    fn test_apply_binary_preview_bytes() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.binary_preview_bytes, DEFAULT_BINARY_PREVIEW_BYTES);
+
+        settings.apply("binary-preview-bytes", "8").unwrap();
+        assert_eq!(settings.binary_preview_bytes, 8);
+        assert!(settings.apply("binary-preview-bytes", "0").is_err());
+    }
+
+    #[test]
+    fn test_apply_sample_json() {
+        let mut settings = Settings::default();
+        assert!(!settings.sample_json);
+
+        settings.apply("sample-json", "on").unwrap();
+        assert!(settings.sample_json);
+        assert!(settings.apply("sample-json", "sometimes").is_err());
+    }
+
+    #[test]
+    fn test_apply_auto_reindex() {
+        let mut settings = Settings::default();
+        assert!(!settings.auto_reindex);
+
+        settings.apply("auto-reindex", "on").unwrap();
+        assert!(settings.auto_reindex);
+        settings.apply("auto-reindex", "off").unwrap();
+        assert!(!settings.auto_reindex);
+    }
+
+    #[test]
+    fn test_apply_nl_fastpath() {
+        let mut settings = Settings::default();
+        assert!(settings.nl_fastpath);
+
+        settings.apply("nl-fastpath", "off").unwrap();
+        assert!(!settings.nl_fastpath);
+        assert!(settings.apply("nl-fastpath", "never").is_err());
+    }
+
+    #[test]
+    fn test_apply_statement_timeout() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.statement_timeout_ms, None);
+
+        settings.apply("statement-timeout", "30000").unwrap();
+        assert_eq!(settings.statement_timeout_ms, Some(30_000));
+        assert_eq!(settings.value("statement-timeout").as_deref(), Some("30000"));
+        settings.apply("statement-timeout", "off").unwrap();
+        assert_eq!(settings.statement_timeout_ms, None);
+        assert!(settings.apply("statement-timeout", "soon").is_err());
+    }
+
+    #[test]
+    fn test_apply_nl_offline() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.nl_offline, NlOfflineMode::Strict);
+
+        settings.apply("nl-offline", "allow").unwrap();
+        assert_eq!(settings.nl_offline, NlOfflineMode::Allow);
+        assert_eq!(settings.value("nl-offline").as_deref(), Some("allow"));
+        assert!(settings.apply("nl-offline", "sometimes").is_err());
+    }
+
+    #[test]
+    fn test_apply_auto_connect() {
+        let mut settings = Settings::default();
+        assert!(settings.auto_connect);
+
+        settings.apply("auto-connect", "off").unwrap();
+        assert!(!settings.auto_connect);
+        assert_eq!(settings.value("auto-connect").as_deref(), Some("off"));
+    }
+
+    #[test]
+    fn test_apply_quiet() {
+        let mut settings = Settings::default();
+        assert!(!settings.quiet);
+
+        settings.apply("quiet", "on").unwrap();
+        assert!(settings.quiet);
+        assert_eq!(settings.value("quiet").as_deref(), Some("on"));
+    }
+
+    #[test]
+    fn test_apply_column_order() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.column_order, ColumnOrder::Ordinal);
+
+        settings.apply("column-order", "alpha").unwrap();
+        assert_eq!(settings.column_order, ColumnOrder::Alpha);
+        assert_eq!(settings.value("column-order").as_deref(), Some("alpha"));
+        assert!(settings.apply("column-order", "reverse").is_err());
+    }
+
+    #[test]
+    fn test_apply_validate_sql() {
+        let mut settings = Settings::default();
+        assert!(!settings.validate_sql);
+
+        settings.apply("validate-sql", "on").unwrap();
+        assert!(settings.validate_sql);
+        assert_eq!(settings.value("validate-sql").as_deref(), Some("on"));
+    }
+
+    #[test]
+    fn test_apply_null_display() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.null_display, "NULL");
+
+        settings.apply("null-display", "(null)").unwrap();
+        assert_eq!(settings.null_display, "(null)");
+
+        settings.apply("null-display", "\"\"").unwrap();
+        assert_eq!(settings.null_display, "");
+        assert_eq!(settings.value("null-display").as_deref(), Some("\"\""));
+    }
+
+    #[test]
+    fn test_apply_max_rows() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.max_rows, Some(DEFAULT_MAX_ROWS));
+
+        settings.apply("max-rows", "500").unwrap();
+        assert_eq!(settings.max_rows, Some(500));
+        settings.apply("max-rows", "off").unwrap();
+        assert_eq!(settings.max_rows, None);
+        assert_eq!(settings.value("max-rows").as_deref(), Some("off"));
+        assert!(settings.apply("max-rows", "lots").is_err());
+    }
+
+    #[test]
+    fn test_apply_include_system_tables() {
+        let mut settings = Settings::default();
+        assert!(!settings.include_system_tables);
+
+        settings.apply("include-system-tables", "on").unwrap();
+        assert!(settings.include_system_tables);
+        assert_eq!(settings.value("include-system-tables").as_deref(), Some("on"));
+        assert!(settings.apply("include-system-tables", "all").is_err());
+    }
+
+    #[test]
+    fn test_apply_retries() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.retries, DEFAULT_MAX_RETRIES);
+
+        settings.apply("retries", "0").unwrap();
+        assert_eq!(settings.retries, 0);
+        assert_eq!(settings.value("retries").as_deref(), Some("0"));
+        assert!(settings.apply("retries", "-1").is_err());
+    }
+
+    #[test]
+    fn test_apply_explain_errors() {
+        let mut settings = Settings::default();
+        assert!(!settings.explain_errors);
+
+        settings.apply("explain-errors", "on").unwrap();
+        assert!(settings.explain_errors);
+        assert_eq!(settings.value("explain-errors").as_deref(), Some("on"));
+        assert!(settings.apply("explain-errors", "maybe").is_err());
+    }
+
+    #[test]
+    fn test_apply_libsql_token() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.value("libsql-token").as_deref(), Some("off"));
+
+        settings.apply("libsql-token", "eyJhbGciOi").unwrap();
+        assert_eq!(settings.libsql_token.as_deref(), Some("eyJhbGciOi"));
+        assert_eq!(settings.value("libsql-token").as_deref(), Some("set"));
+        settings.apply("libsql-token", "off").unwrap();
+        assert_eq!(settings.libsql_token, None);
+    }
+
+    #[test]
+    fn test_apply_pretty_sql() {
+        let mut settings = Settings::default();
+        assert!(settings.pretty_sql);
+
+        settings.apply("pretty-sql", "off").unwrap();
+        assert!(!settings.pretty_sql);
+    }
+
+    #[test]
+    fn test_apply_number_grouping() {
+        let mut settings = Settings::default();
+        assert!(!settings.number_grouping);
+
+        settings.apply("number-grouping", "on").unwrap();
+        assert!(settings.number_grouping);
+        assert_eq!(settings.value("number-grouping").as_deref(), Some("on"));
+        assert!(settings.apply("number-grouping", "maybe").is_err());
+    }
+
+    #[test]
+    fn test_apply_big_table_rows() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.big_table_rows, DEFAULT_BIG_TABLE_ROWS);
+
+        settings.apply("big-table-rows", "5000").unwrap();
+        assert_eq!(settings.big_table_rows, 5000);
+        assert!(settings.apply("big-table-rows", "0").is_err());
+    }
+}
@@ -3,14 +3,48 @@
 //! This module handles persistent storage of configuration data
 //! including API keys, model settings, and user preferences.
 
+use crate::config::settings::Settings;
 use crate::error::{Result, SchemaForgeError};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 /// Configuration file name
 const CONFIG_FILE: &str = "config.toml";
 
+/// Environment variable that overrides the configuration directory
+pub const CONFIG_DIR_ENV: &str = "SCHEMA_FORGE_CONFIG_DIR";
+
+/// Directory given by `--config-dir`, which wins over the environment
+static CONFIG_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Pick the configuration directory: the `--config-dir` flag, then a
+/// non-empty `$SCHEMA_FORGE_CONFIG_DIR`, then the default
+fn resolve_config_dir(
+    flag: Option<PathBuf>,
+    env: Option<std::ffi::OsString>,
+    default: Option<PathBuf>,
+) -> Option<PathBuf> {
+    flag.or_else(|| env.filter(|dir| !dir.is_empty()).map(PathBuf::from))
+        .or(default)
+}
+
+/// The OS configuration directory
+///
+/// Unit tests use a per-process temporary directory instead, so saves made
+/// by command handlers never reach the user's real config.
+fn default_config_dir() -> Option<PathBuf> {
+    if cfg!(test) {
+        return Some(std::env::temp_dir().join(format!(
+            "schema-forge-test-config-{}",
+            std::process::id()
+        )));
+    }
+    dirs::config_dir().map(|dir| dir.join("schema-forge"))
+}
+
 /// Persistent configuration data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -20,6 +54,12 @@ pub struct Config {
     pub models: std::collections::HashMap<String, String>,
     /// Current selected provider
     pub current_provider: Option<String>,
+    /// Behaviour settings changed with /config <setting> <value>
+    #[serde(default)]
+    pub settings: Settings,
+    /// Extra HTTP headers sent with every request, per provider
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub headers: std::collections::HashMap<String, std::collections::HashMap<String, String>>,
 }
 
 impl Default for Config {
@@ -28,6 +68,8 @@ impl Default for Config {
             api_keys: std::collections::HashMap::new(),
             models: Self::default_models(),
             current_provider: None,
+            settings: Settings::default(),
+            headers: std::collections::HashMap::new(),
         }
     }
 }
@@ -35,20 +77,7 @@ impl Default for Config {
 impl Config {
     /// Get default models for each provider
     pub fn default_models() -> std::collections::HashMap<String, String> {
-        let mut models = std::collections::HashMap::new();
-
-        // Current as of 2025
-        models.insert("anthropic".to_string(), "claude-sonnet-4-20250514".to_string());
-        models.insert("openai".to_string(), "gpt-4o".to_string());
-        models.insert("ollama".to_string(), "llama3.2".to_string());
-        models.insert("groq".to_string(), "llama-3.3-70b-versatile".to_string());
-        models.insert("cohere".to_string(), "command-r-plus".to_string());
-        models.insert("xai".to_string(), "grok-2".to_string());
-        models.insert("minimax".to_string(), "abab6.5s-chat".to_string());
-        models.insert("qwen".to_string(), "qwen-max".to_string());
-        models.insert("zai".to_string(), "deepseek-r1".to_string());
-
-        models
+        crate::llm::provider_registry::default_models()
     }
 
     /// Create a new empty configuration
@@ -56,14 +85,27 @@ impl Config {
         Self::default()
     }
 
+    /// Use `dir` as the configuration directory for the rest of the process
+    ///
+    /// Only the first call takes effect; returns whether this one did.
+    pub fn set_config_dir(dir: impl Into<PathBuf>) -> bool {
+        CONFIG_DIR_OVERRIDE.set(dir.into()).is_ok()
+    }
+
     /// Get the configuration directory path
+    ///
+    /// `--config-dir` wins, then `$SCHEMA_FORGE_CONFIG_DIR`, then the OS
+    /// config directory.
     pub fn config_dir() -> Result<PathBuf> {
-        let config_dir = dirs::config_dir()
-            .ok_or_else(|| SchemaForgeError::Io(std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                "Could not find configuration directory",
-            )))?
-            .join("schema-forge");
+        let config_dir = resolve_config_dir(
+            CONFIG_DIR_OVERRIDE.get().cloned(),
+            std::env::var_os(CONFIG_DIR_ENV),
+            default_config_dir(),
+        )
+        .ok_or_else(|| SchemaForgeError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "Could not find configuration directory",
+        )))?;
 
         // Ensure directory exists
         fs::create_dir_all(&config_dir).map_err(|e| {
@@ -97,20 +139,71 @@ impl Config {
             ))
         })?;
 
-        let config: Config = toml::from_str(&content).map_err(|e| {
+        let mut config: Config = toml::from_str(&content).map_err(|e| {
             SchemaForgeError::Io(std::io::Error::new(
                 std::io::ErrorKind::Other,
                 format!("Failed to parse config file: {}", e),
             ))
         })?;
+        config.normalize_provider_names();
 
         Ok(config)
     }
 
+    /// Load configuration, setting aside a config file that cannot be parsed
+    ///
+    /// Returns the configuration and, if the file had to be set aside, a
+    /// warning naming the problem and the backup path.
+    pub fn load_or_recover() -> Result<(Self, Option<String>)> {
+        Self::load_or_recover_from(&Self::config_file()?)
+    }
+
+    /// Load configuration from `path`, setting it aside if it cannot be parsed
+    ///
+    /// A file that is not UTF-8 or not valid TOML is renamed to
+    /// `<name>.bak` and a fresh configuration is returned, so saved keys
+    /// are never silently overwritten.
+    pub fn load_or_recover_from(path: &Path) -> Result<(Self, Option<String>)> {
+        if !path.exists() {
+            return Ok((Self::new(), None));
+        }
+
+        let problem = match String::from_utf8(fs::read(path)?) {
+            Ok(content) => match toml::from_str::<Config>(&content) {
+                Ok(mut config) => {
+                    config.normalize_provider_names();
+                    return Ok((config, None));
+                }
+                Err(e) => format!("invalid TOML: {}", e.message()),
+            },
+            Err(_) => "the file is not valid UTF-8".to_string(),
+        };
+
+        let mut backup = path.as_os_str().to_owned();
+        backup.push(".bak");
+        let backup = PathBuf::from(backup);
+        fs::rename(path, &backup)?;
+
+        let warning = format!(
+            "Warning: could not load {} ({}). It was moved to {} and a fresh configuration is in use.",
+            path.display(),
+            problem,
+            backup.display()
+        );
+        Ok((Self::new(), Some(warning)))
+    }
+
     /// Save configuration to disk
     pub fn save(&self) -> Result<()> {
-        let config_file = Self::config_file()?;
+        self.save_to(&Self::config_file()?)
+    }
 
+    /// Save configuration to `path` atomically
+    ///
+    /// The file is written to a temporary file beside `path` and renamed into
+    /// place, so a crash mid-write leaves the previous config intact. On Unix
+    /// the file is readable by its owner only, since it holds API keys.
+    pub fn save_to(&self, path: &Path) -> Result<()> {
         let content = toml::to_string_pretty(self).map_err(|e| {
             SchemaForgeError::Io(std::io::Error::new(
                 std::io::ErrorKind::Other,
@@ -118,12 +211,16 @@ impl Config {
             ))
         })?;
 
-        fs::write(&config_file, content).map_err(|e| {
-            SchemaForgeError::Io(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                format!("Failed to write config file: {}", e),
-            ))
-        })?;
+        let temp_file = temp_path(path);
+        write_private(&temp_file, content.as_bytes())
+            .and_then(|()| fs::rename(&temp_file, path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&temp_file);
+                SchemaForgeError::Io(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    format!("Failed to write config file: {}", e),
+                ))
+            })?;
 
         Ok(())
     }
@@ -157,6 +254,122 @@ impl Config {
     pub fn list_providers(&self) -> Vec<String> {
         self.api_keys.keys().cloned().collect()
     }
+
+    /// Providers named in the config that the provider registry does not know
+    ///
+    /// Checks API keys, model overrides, headers and the current provider; the result
+    /// is sorted and has no duplicates.
+    pub fn unknown_providers(&self) -> Vec<String> {
+        let mut unknown: Vec<String> = self
+            .api_keys
+            .keys()
+            .chain(self.models.keys())
+            .chain(self.headers.keys())
+            .chain(self.current_provider.iter())
+            .filter(|name| !crate::llm::provider_registry::is_known_provider(name))
+            .cloned()
+            .collect();
+        unknown.sort();
+        unknown.dedup();
+        unknown
+    }
+
+    /// Move a provider's key, model, headers and current selection to `new`
+    ///
+    /// Values under `old` replace any already under `new`. Returns whether
+    /// `old` had anything to move.
+    pub fn rename_provider(&mut self, old: &str, new: &str) -> bool {
+        if old == new {
+            return self.api_keys.contains_key(old)
+                || self.models.contains_key(old)
+                || self.headers.contains_key(old);
+        }
+        let mut moved = false;
+        if let Some(key) = self.api_keys.remove(old) {
+            self.api_keys.insert(new.to_string(), key);
+            moved = true;
+        }
+        if let Some(model) = self.models.remove(old) {
+            self.models.insert(new.to_string(), model);
+            moved = true;
+        }
+        if let Some(headers) = self.headers.remove(old) {
+            self.headers.insert(new.to_string(), headers);
+            moved = true;
+        }
+        if self.current_provider.as_deref() == Some(old) {
+            self.current_provider = Some(new.to_string());
+            moved = true;
+        }
+        moved
+    }
+
+    /// Store every registered provider under its registry name (`z.ai` -> `zai`)
+    ///
+    /// An alias's values win over the registry name's, since the registry
+    /// name's model is usually just the built-in default. Returns the
+    /// `(old, new)` names that were merged.
+    pub fn normalize_provider_names(&mut self) -> Vec<(String, String)> {
+        let mut aliases: Vec<String> = self
+            .api_keys
+            .keys()
+            .chain(self.models.keys())
+            .chain(self.headers.keys())
+            .chain(self.current_provider.iter())
+            .filter(|name| crate::llm::provider_registry::canonical_name(name) != **name)
+            .cloned()
+            .collect();
+        aliases.sort();
+        aliases.dedup();
+
+        aliases
+            .into_iter()
+            .map(|alias| {
+                let canonical = crate::llm::provider_registry::canonical_name(&alias);
+                self.rename_provider(&alias, &canonical);
+                (alias, canonical)
+            })
+            .collect()
+    }
+
+    /// Forget keys, models, headers and the current selection for these providers
+    pub fn remove_providers(&mut self, providers: &[String]) {
+        for provider in providers {
+            self.api_keys.remove(provider);
+            self.models.remove(provider);
+            self.headers.remove(provider);
+            if self.current_provider.as_ref() == Some(provider) {
+                self.current_provider = None;
+            }
+        }
+    }
+}
+
+/// Temporary file used while saving `path`
+fn temp_path(path: &Path) -> PathBuf {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    PathBuf::from(temp)
+}
+
+/// Create or truncate `path` with owner-only permissions and flush `content`
+pub(crate) fn write_private(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(content)?;
+    file.sync_all()
 }
 
 #[cfg(test)]
@@ -187,4 +400,158 @@ mod tests {
         config.remove_model("anthropic");
         assert_eq!(config.get_model("anthropic"), None);
     }
+
+    #[test]
+    fn test_unknown_providers_are_identified() {
+        let mut config = Config::new();
+        config.set_api_key("openai".to_string(), "sk-1".to_string());
+        config.set_api_key("bard".to_string(), "old-key".to_string());
+        config.set_model("bard".to_string(), "bard-1".to_string());
+        config.current_provider = Some("bard".to_string());
+
+        assert_eq!(config.unknown_providers(), vec!["bard".to_string()]);
+
+        config.remove_providers(&config.unknown_providers());
+        assert!(config.unknown_providers().is_empty());
+        assert!(config.get_api_key("openai").is_some());
+        assert_eq!(config.current_provider, None);
+    }
+
+    #[test]
+    fn test_config_dir_resolution_order() {
+        let flag = PathBuf::from("/flag");
+        let env = PathBuf::from("/env");
+        let default = PathBuf::from("/default");
+
+        let resolve = |flag: Option<&PathBuf>, env: Option<&str>| {
+            resolve_config_dir(flag.cloned(), env.map(Into::into), Some(default.clone()))
+        };
+        assert_eq!(resolve(Some(&flag), Some("/env")), Some(flag.clone()));
+        assert_eq!(resolve(None, Some("/env")), Some(env));
+        assert_eq!(resolve(None, Some("")), Some(default.clone()));
+        assert_eq!(resolve(None, None), Some(default.clone()));
+        assert_eq!(resolve_config_dir(None, None, None), None);
+
+        // Unit tests never fall back to the user's real config directory
+        if CONFIG_DIR_OVERRIDE.get().is_none() && std::env::var_os(CONFIG_DIR_ENV).is_none() {
+            assert!(Config::config_dir().unwrap().starts_with(std::env::temp_dir()));
+        }
+    }
+
+    #[test]
+    fn test_config_dir_env_redirects_load_and_save() {
+        let dir = test_dir("env").join("nested");
+        let resolved = resolve_config_dir(None, Some(dir.clone().into_os_string()), None).unwrap();
+        fs::create_dir_all(&resolved).unwrap();
+
+        let mut config = Config::new();
+        config.set_model("groq".to_string(), "llama-3.3-70b-versatile".to_string());
+        config.save_to(&resolved.join(CONFIG_FILE)).unwrap();
+
+        assert!(dir.join(CONFIG_FILE).exists());
+        let (loaded, warning) = Config::load_or_recover_from(&dir.join(CONFIG_FILE)).unwrap();
+        assert!(warning.is_none());
+        assert_eq!(loaded.get_model("groq").as_deref(), Some("llama-3.3-70b-versatile"));
+    }
+
+    #[test]
+    fn test_provider_aliases_coalesce_on_load() {
+        let dir = test_dir("aliases");
+        let path = dir.join(CONFIG_FILE);
+        fs::write(
+            &path,
+            r#"
+current_provider = "z.ai"
+
+[api_keys]
+"z.ai" = "zai-key"
+openai = "sk-test"
+
+[models]
+zai = "glm-4.5"
+"Z.AI" = "glm-4.6"
+"#,
+        )
+        .unwrap();
+
+        let (config, warning) = Config::load_or_recover_from(&path).unwrap();
+        assert!(warning.is_none());
+        assert_eq!(config.get_api_key("zai").map(String::as_str), Some("zai-key"));
+        assert_eq!(config.get_model("zai").as_deref(), Some("glm-4.6"));
+        assert_eq!(config.current_provider.as_deref(), Some("zai"));
+        assert!(!config.api_keys.contains_key("z.ai"));
+        assert!(!config.models.contains_key("Z.AI"));
+        assert!(config.unknown_providers().is_empty());
+
+        let mut config = config;
+        assert!(config.rename_provider("openai", "my-openai"));
+        assert_eq!(config.get_api_key("my-openai").map(String::as_str), Some("sk-test"));
+        assert!(!config.rename_provider("openai", "my-openai"));
+    }
+
+    /// Fresh, empty directory for a test's config files
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "schema-forge-config-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_save_is_atomic_and_private() {
+        let dir = test_dir("save");
+        let path = dir.join(CONFIG_FILE);
+
+        let mut config = Config::new();
+        config.set_api_key("openai".to_string(), "sk-original".to_string());
+        config.save_to(&path).unwrap();
+        assert!(!temp_path(&path).exists());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // A write that never reaches the rename leaves the original untouched
+        write_private(&temp_path(&path), b"api_keys = { openai = \"sk-parti").unwrap();
+        let (loaded, warning) = Config::load_or_recover_from(&path).unwrap();
+        assert!(warning.is_none());
+        assert_eq!(loaded.get_api_key("openai").map(String::as_str), Some("sk-original"));
+
+        config.set_api_key("openai".to_string(), "sk-updated".to_string());
+        config.save_to(&path).unwrap();
+        let (loaded, _) = Config::load_or_recover_from(&path).unwrap();
+        assert_eq!(loaded.get_api_key("openai").map(String::as_str), Some("sk-updated"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt_config_is_backed_up() {
+        let dir = test_dir("corrupt");
+        let path = dir.join(CONFIG_FILE);
+        fs::write(&path, "api_keys = { openai = \"sk-1\"\n[[broken").unwrap();
+
+        let (config, warning) = Config::load_or_recover_from(&path).unwrap();
+        assert!(config.api_keys.is_empty());
+        let warning = warning.unwrap();
+        assert!(warning.contains("invalid TOML"));
+        assert!(warning.contains("config.toml.bak"));
+        assert!(!path.exists());
+        let backup = fs::read_to_string(dir.join("config.toml.bak")).unwrap();
+        assert!(backup.contains("sk-1"));
+
+        fs::write(&path, [0xff, 0xfe, 0x00]).unwrap();
+        let (_, warning) = Config::load_or_recover_from(&path).unwrap();
+        assert!(warning.unwrap().contains("UTF-8"));
+
+        let (_, warning) = Config::load_or_recover_from(&path).unwrap();
+        assert!(warning.is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -4,13 +4,54 @@
 //! Each database type has its own indexing function that queries the system catalogs
 //! and builds a complete SchemaIndex.
 
-use crate::database::schema::{Column, ColumnType, ForeignKeyReference, SchemaIndex, Table, TableRelationship};
+use crate::database::schema::{
+    Column, ColumnType, ForeignKeyReference, SchemaIndex, Table, TableIndex, TableRelationship,
+};
 use crate::error::{Result, SchemaForgeError};
+use crate::database::connection::{libsql_query, libsql_value_text};
+use libsql::Connection as LibsqlConnection;
 use oracle_rs::Connection as OracleConnection;
 use sqlx::{postgres::PgPool, mysql::MySqlPool, sqlite::SqlitePool, Row};
 
+/// Called once per table while indexing, with the table's name, its
+/// 1-based position and the number of tables
+pub type IndexProgress<'a> = &'a mut (dyn FnMut(&str, usize, usize) + Send);
+
+/// Which kinds of object an index run enumerates
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IndexScope {
+    /// Tables and views
+    #[default]
+    All,
+    /// Base tables only
+    TablesOnly,
+    /// Views only
+    ViewsOnly,
+}
+
+impl IndexScope {
+    /// Extra `AND ...` condition on a catalog's object type column, where
+    /// `view_type` is the value that column holds for views
+    fn catalog_filter(self, type_column: &str, view_type: &str) -> String {
+        match self {
+            IndexScope::All => String::new(),
+            IndexScope::TablesOnly => format!("AND {} <> '{}'", type_column, view_type),
+            IndexScope::ViewsOnly => format!("AND {} = '{}'", type_column, view_type),
+        }
+    }
+}
+
 /// Index PostgreSQL database schema
 pub async fn index_postgresql(pool: &PgPool) -> Result<SchemaIndex> {
+    index_postgresql_with_progress(pool, IndexScope::All, &mut |_, _, _| {}).await
+}
+
+/// Index PostgreSQL database schema, reporting each table to `progress`
+pub async fn index_postgresql_with_progress(
+    pool: &PgPool,
+    scope: IndexScope,
+    progress: IndexProgress<'_>,
+) -> Result<SchemaIndex> {
     let mut schema_index = SchemaIndex::new();
 
     // Get database name
@@ -23,23 +64,29 @@ pub async fn index_postgresql(pool: &PgPool) -> Result<SchemaIndex> {
     schema_index.schema_name = Some("public".to_string());
 
     // Query all tables and views
-    let tables_query = r#"
+    let tables_query = format!(
+        r#"
         SELECT
             table_name,
             table_type,
             obj_description((table_schema||'.'||table_name)::regclass, 'pg_class') as comment
         FROM information_schema.tables
         WHERE table_schema = 'public'
+            {}
         ORDER BY table_name
-    "#;
+    "#,
+        scope.catalog_filter("table_type", "VIEW")
+    );
 
-    let tables_rows = sqlx::query(tables_query)
+    let tables_rows = sqlx::query(&tables_query)
         .fetch_all(pool)
         .await
-        .map_err(|e| SchemaForgeError::db_query(tables_query, e))?;
+        .map_err(|e| SchemaForgeError::db_query(&tables_query, e))?;
 
-    for row in tables_rows {
+    let total = tables_rows.len();
+    for (position, row) in tables_rows.into_iter().enumerate() {
         let table_name: String = row.get("table_name");
+        progress(&table_name, position + 1, total);
         let table_type: String = row.get("table_type");
         let comment: Option<String> = row.get("comment");
 
@@ -61,6 +108,7 @@ pub async fn index_postgresql(pool: &PgPool) -> Result<SchemaIndex> {
                 numeric_scale,
                 is_nullable,
                 column_default,
+                is_identity,
                 ordinal_position
             FROM information_schema.columns
             WHERE table_schema = 'public'
@@ -82,16 +130,16 @@ pub async fn index_postgresql(pool: &PgPool) -> Result<SchemaIndex> {
             let scale: Option<i64> = col_row.get("numeric_scale");
             let is_nullable: String = col_row.get("is_nullable");
             let default_val: Option<String> = col_row.get("column_default");
+            let is_identity: Option<String> = col_row.get("is_identity");
+            let is_auto_increment =
+                is_postgres_auto_increment(default_val.as_deref(), is_identity.as_deref());
 
+            let (element_type, array_dimensions) = ColumnType::split_array_type(&data_type);
             let column_type = ColumnType {
-                base_type: data_type.clone(),
+                base_type: element_type.to_string(),
                 length: max_len.or(precision),
                 scale,
-                array_dimensions: if data_type.ends_with("[]") {
-                    Some(1)
-                } else {
-                    None
-                },
+                array_dimensions,
             };
 
             let column = Column {
@@ -104,6 +152,7 @@ pub async fn index_postgresql(pool: &PgPool) -> Result<SchemaIndex> {
                 references: None,
                 is_unique: false,
                 comment: None,
+                is_auto_increment,
             };
 
             table.add_column(column);
@@ -185,43 +234,107 @@ pub async fn index_postgresql(pool: &PgPool) -> Result<SchemaIndex> {
             schema_index.relationships.push(relationship);
         }
 
+        // Query secondary indexes
+        let index_query = r#"
+            SELECT
+                ic.relname AS index_name,
+                a.attname AS column_name,
+                i.indisunique AS is_unique
+            FROM pg_index i
+            JOIN pg_class ic ON ic.oid = i.indexrelid
+            JOIN pg_attribute a ON a.attrelid = i.indrelid AND a.attnum = ANY(i.indkey)
+            WHERE i.indrelid = $1::regclass AND NOT i.indisprimary
+            ORDER BY ic.relname, array_position(i.indkey::int2[], a.attnum)
+        "#;
+
+        let index_rows = sqlx::query(index_query)
+            .bind(&table_name)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| SchemaForgeError::db_query(index_query, e))?;
+
+        table.indexes = group_index_columns(index_rows.iter().map(|row| {
+            (
+                row.get::<String, _>("index_name"),
+                row.get::<String, _>("column_name"),
+                row.get::<bool, _>("is_unique"),
+            )
+        }));
+
         schema_index.add_table(table);
     }
 
+    // Partitions and inheriting tables, noted on the child
+    let inherits_query = r#"
+        SELECT
+            child.relname AS child_name,
+            parent.relname AS parent_name,
+            child.relispartition AS is_partition
+        FROM pg_inherits i
+        JOIN pg_class child ON child.oid = i.inhrelid
+        JOIN pg_class parent ON parent.oid = i.inhparent
+        JOIN pg_namespace n ON n.oid = child.relnamespace
+        WHERE n.nspname = 'public'
+    "#;
+
+    let inherits_rows = sqlx::query(inherits_query)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| SchemaForgeError::db_query(inherits_query, e))?;
+
+    for row in inherits_rows {
+        let child_name: String = row.get("child_name");
+        if let Some(table) = schema_index.tables.get_mut(&child_name) {
+            table.partition_of = Some(row.get("parent_name"));
+            table.is_partition = row.get("is_partition");
+        }
+    }
+
     Ok(schema_index)
 }
 
-/// Index MySQL database schema
-pub async fn index_mysql(pool: &MySqlPool) -> Result<SchemaIndex> {
-    let mut schema_index = SchemaIndex::new();
+/// Index the tables of one MySQL database (`schema`)
+pub async fn index_mysql(pool: &MySqlPool, schema: &str) -> Result<SchemaIndex> {
+    index_mysql_with_progress(pool, schema, IndexScope::All, &mut |_, _, _| {}).await
+}
 
-    // Get database name
-    let db_row: Option<(String,)> = sqlx::query_as("SELECT DATABASE()")
-        .fetch_optional(pool)
-        .await?;
-    if let Some((db_name,)) = db_row {
-        schema_index.database_name = Some(db_name);
-    }
+/// Index one MySQL database, reporting each table to `progress`
+pub async fn index_mysql_with_progress(
+    pool: &MySqlPool,
+    schema: &str,
+    scope: IndexScope,
+    progress: IndexProgress<'_>,
+) -> Result<SchemaIndex> {
+    let mut schema_index = SchemaIndex::new();
+    schema_index.database_name = Some(schema.to_string());
+    schema_index.schema_name = Some(schema.to_string());
 
     // Query all tables and views
-    let tables_query = r#"
+    let tables_query = format!(
+        r#"
         SELECT
             TABLE_NAME as table_name,
             TABLE_TYPE as table_type,
             TABLE_COMMENT as comment
         FROM information_schema.TABLES
-        WHERE TABLE_SCHEMA = DATABASE()
+        WHERE TABLE_SCHEMA = ?
             AND TABLE_TYPE IN ('BASE TABLE', 'VIEW')
+            {}
         ORDER BY TABLE_NAME
-    "#;
+    "#,
+        scope.catalog_filter("TABLE_TYPE", "VIEW")
+    );
 
-    let tables_rows = sqlx::query(tables_query)
+    let tables_rows = sqlx::query(&tables_query)
+        .bind(schema)
         .fetch_all(pool)
         .await
-        .map_err(|e| SchemaForgeError::db_query(tables_query, e))?;
+        .map_err(|e| SchemaForgeError::db_query(&tables_query, e))?;
 
-    for row in tables_rows {
+    let total = tables_rows.len();
+    for (position, row) in tables_rows.into_iter().enumerate() {
         let table_name: String = row.get("table_name");
+        progress(&table_name, position + 1, total);
         let table_type: String = row.get("table_type");
         let comment: Option<String> = row.get("comment");
 
@@ -244,14 +357,16 @@ pub async fn index_mysql(pool: &MySqlPool) -> Result<SchemaIndex> {
                 IS_NULLABLE as is_nullable,
                 COLUMN_DEFAULT as column_default,
                 COLUMN_KEY as column_key,
+                EXTRA as extra,
                 ORDINAL_POSITION as ordinal_position
             FROM information_schema.COLUMNS
-            WHERE TABLE_SCHEMA = DATABASE()
-                AND TABLE_NAME = $1
+            WHERE TABLE_SCHEMA = ?
+                AND TABLE_NAME = ?
             ORDER BY ORDINAL_POSITION
         "#;
 
         let columns_rows = sqlx::query(columns_query)
+            .bind(schema)
             .bind(&table_name)
             .fetch_all(pool)
             .await
@@ -266,6 +381,7 @@ pub async fn index_mysql(pool: &MySqlPool) -> Result<SchemaIndex> {
             let is_nullable: String = col_row.get("is_nullable");
             let default_val: Option<String> = col_row.get("column_default");
             let column_key: Option<String> = col_row.get("column_key");
+            let extra: Option<String> = col_row.get("extra");
 
             let column_type = ColumnType {
                 base_type: data_type,
@@ -286,6 +402,7 @@ pub async fn index_mysql(pool: &MySqlPool) -> Result<SchemaIndex> {
                 references: None,
                 is_unique: column_key.as_deref() == Some("UNI"),
                 comment: None,
+                is_auto_increment: is_mysql_auto_increment(extra.as_deref()),
             };
 
             if is_pk {
@@ -302,12 +419,13 @@ pub async fn index_mysql(pool: &MySqlPool) -> Result<SchemaIndex> {
                 REFERENCED_TABLE_NAME as foreign_table_name,
                 REFERENCED_COLUMN_NAME as foreign_column_name
             FROM information_schema.KEY_COLUMN_USAGE
-            WHERE TABLE_SCHEMA = DATABASE()
-                AND TABLE_NAME = $1
+            WHERE TABLE_SCHEMA = ?
+                AND TABLE_NAME = ?
                 AND REFERENCED_TABLE_NAME IS NOT NULL
         "#;
 
         let fk_rows = sqlx::query(fk_query)
+            .bind(schema)
             .bind(&table_name)
             .fetch_all(pool)
             .await
@@ -341,6 +459,34 @@ pub async fn index_mysql(pool: &MySqlPool) -> Result<SchemaIndex> {
             schema_index.relationships.push(relationship);
         }
 
+        // Query secondary indexes
+        let index_query = r#"
+            SELECT
+                INDEX_NAME as index_name,
+                COLUMN_NAME as column_name,
+                NON_UNIQUE as non_unique
+            FROM information_schema.STATISTICS
+            WHERE TABLE_SCHEMA = ?
+                AND TABLE_NAME = ?
+                AND INDEX_NAME <> 'PRIMARY'
+            ORDER BY INDEX_NAME, SEQ_IN_INDEX
+        "#;
+
+        let index_rows = sqlx::query(index_query)
+            .bind(schema)
+            .bind(&table_name)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| SchemaForgeError::db_query(index_query, e))?;
+
+        table.indexes = group_index_columns(index_rows.iter().map(|row| {
+            (
+                row.get::<String, _>("index_name"),
+                row.get::<String, _>("column_name"),
+                row.get::<i64, _>("non_unique") == 0,
+            )
+        }));
+
         schema_index.add_table(table);
     }
 
@@ -349,26 +495,77 @@ pub async fn index_mysql(pool: &MySqlPool) -> Result<SchemaIndex> {
 
 /// Index SQLite database schema
 pub async fn index_sqlite(pool: &SqlitePool) -> Result<SchemaIndex> {
+    index_sqlite_with_progress(pool, IndexScope::All, &mut |_, _, _| {}).await
+}
+
+/// Index SQLite database schema, reporting each table to `progress`
+///
+/// Positions count within each schema, so attached databases start again at 1.
+pub async fn index_sqlite_with_progress(
+    pool: &SqlitePool,
+    scope: IndexScope,
+    progress: IndexProgress<'_>,
+) -> Result<SchemaIndex> {
     let mut schema_index = SchemaIndex::new();
     schema_index.database_name = Some("main".to_string());
     schema_index.schema_name = Some("main".to_string());
 
-    // Query all tables
-    let tables_query = r#"
+    // Attached databases appear alongside main; temp objects are session-local
+    let databases_query = "SELECT name FROM pragma_database_list ORDER BY seq";
+    let schemas: Vec<String> = sqlx::query(databases_query)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| SchemaForgeError::db_query(databases_query, e))?
+        .iter()
+        .map(|row| row.get::<String, _>("name"))
+        .filter(|name| name != "temp")
+        .collect();
+
+    for schema in &schemas {
+        index_sqlite_schema(pool, schema, scope, &mut schema_index, &mut *progress).await?;
+    }
+
+    Ok(schema_index)
+}
+
+/// Index the tables of one SQLite schema (`main` or an attached alias)
+///
+/// Tables in attached schemas are qualified as `alias.table`; tables in
+/// `main` keep their bare names.
+async fn index_sqlite_schema(
+    pool: &SqlitePool,
+    schema: &str,
+    scope: IndexScope,
+    schema_index: &mut SchemaIndex,
+    progress: IndexProgress<'_>,
+) -> Result<()> {
+    let quoted_schema = format!("\"{}\"", schema.replace('"', "\"\""));
+    let tables_query = format!(
+        r#"
         SELECT name, type
-        FROM sqlite_master
+        FROM {}.sqlite_master
         WHERE type IN ('table', 'view')
             AND name NOT LIKE 'sqlite_%'
+            {}
         ORDER BY name
-    "#;
+    "#,
+        quoted_schema,
+        scope.catalog_filter("type", "view")
+    );
 
-    let tables_rows = sqlx::query(tables_query)
+    let tables_rows = sqlx::query(&tables_query)
         .fetch_all(pool)
         .await
-        .map_err(|e| SchemaForgeError::db_query(tables_query, e))?;
+        .map_err(|e| SchemaForgeError::db_query(&tables_query, e))?;
 
-    for row in tables_rows {
+    let total = tables_rows.len();
+    for (position, row) in tables_rows.into_iter().enumerate() {
         let table_name: String = row.get("name");
+        if schema == "main" {
+            progress(&table_name, position + 1, total);
+        } else {
+            progress(&format!("{}.{}", schema, table_name), position + 1, total);
+        }
         let table_type: String = row.get("type");
 
         let is_view = table_type == "view";
@@ -377,78 +574,402 @@ pub async fn index_sqlite(pool: &SqlitePool) -> Result<SchemaIndex> {
         } else {
             Table::new(&table_name)
         };
+        if schema != "main" {
+            table.schema = Some(schema.to_string());
+        }
 
         // Get CREATE TABLE/VIEW SQL to parse columns
-        let create_sql_query = "SELECT sql FROM sqlite_master WHERE name = $1";
-        let create_sql_row: Option<(String,)> = sqlx::query_as(create_sql_query)
+        let create_sql_query = format!("SELECT sql FROM {}.sqlite_master WHERE name = $1", quoted_schema);
+        let create_sql_row: Option<(String,)> = sqlx::query_as(&create_sql_query)
             .bind(&table_name)
             .fetch_optional(pool)
             .await
-            .map_err(|e| SchemaForgeError::db_query(create_sql_query, e))?;
+            .map_err(|e| SchemaForgeError::db_query(&create_sql_query, e))?;
+
+        let column_info = sqlite_column_info(pool, schema, &table_name).await?;
+        let mut pk_columns: Vec<&SqliteColumnInfo> =
+            column_info.iter().filter(|info| info.pk_position > 0).collect();
+        pk_columns.sort_by_key(|info| info.pk_position);
+        table.primary_keys = pk_columns.iter().map(|info| info.name.clone()).collect();
 
         if let Some((sql,)) = create_sql_row {
             table.comment = Some(sql.clone());
+            for column in sqlite_columns_from_create_sql(&sql, &column_info) {
+                table.add_column(column);
+            }
+        }
 
-            // Parse the CREATE statement to extract columns
-            if let Some(columns_start) = sql.find('(') {
-                let columns_str = &sql[columns_start + 1..];
-                if let Some(columns_end) = columns_str.rfind(')') {
-                    let columns_def = &columns_str[..columns_end];
-
-                    for column_def in columns_def.split(',') {
-                        let column_def = column_def.trim();
-                        if column_def.to_uppercase().starts_with("PRIMARY KEY")
-                            || column_def.to_uppercase().starts_with("FOREIGN KEY")
-                            || column_def.to_uppercase().starts_with("UNIQUE")
-                            || column_def.to_uppercase().starts_with("CHECK")
-                            || column_def.to_uppercase().starts_with("CONSTRAINT") {
-                            continue;
-                        }
+        if !is_view {
+            table.indexes = index_sqlite_indexes(pool, schema, &table_name).await?;
+        }
 
-                        let parts: Vec<&str> = column_def.split_whitespace().collect();
-                        if parts.is_empty() {
-                            continue;
-                        }
+        schema_index.add_table(table);
+    }
 
-                        let column_name = parts[0].to_string();
-                        let data_type = if parts.len() > 1 {
-                            parts[1].to_string()
-                        } else {
-                            "TEXT".to_string()
-                        };
+    Ok(())
+}
 
-                        // Parse constraints
-                        let is_pk = column_def.to_uppercase().contains("PRIMARY KEY");
-                        let is_nullable = !column_def.to_uppercase().contains("NOT NULL");
-                        let is_unique = column_def.to_uppercase().contains("UNIQUE");
+/// Build the columns of a SQLite table from its CREATE statement
+///
+/// Types come from the statement; nullability, defaults and keys come from
+/// `PRAGMA table_info`.
+fn sqlite_columns_from_create_sql(sql: &str, column_info: &[SqliteColumnInfo]) -> Vec<Column> {
+    let mut columns = Vec::new();
+    let pk_count = column_info.iter().filter(|info| info.pk_position > 0).count();
+
+    // Parse the CREATE statement to extract columns
+    let Some(columns_start) = sql.find('(') else {
+        return columns;
+    };
+    let columns_str = &sql[columns_start + 1..];
+    let Some(columns_end) = columns_str.rfind(')') else {
+        return columns;
+    };
+    let columns_def = &columns_str[..columns_end];
+
+    for column_def in split_column_definitions(columns_def) {
+        let column_def = column_def.as_str();
+        if column_def.to_uppercase().starts_with("PRIMARY KEY")
+            || column_def.to_uppercase().starts_with("FOREIGN KEY")
+            || column_def.to_uppercase().starts_with("UNIQUE")
+            || column_def.to_uppercase().starts_with("CHECK")
+            || column_def.to_uppercase().starts_with("CONSTRAINT") {
+            continue;
+        }
 
-                        if is_pk {
-                            table.primary_keys.push(column_name.clone());
-                        }
+        let (column_name, rest) = split_leading_identifier(column_def);
+        if column_name.is_empty() {
+            continue;
+        }
+
+        let data_type = leading_type(rest).unwrap_or_else(|| "TEXT".to_string());
+
+        let info = column_info.iter().find(|info| info.name == column_name);
+        let is_pk = info.map(|info| info.pk_position > 0).unwrap_or(false);
+        let is_nullable = info.map(|info| !info.not_null).unwrap_or(true);
+        let default_value = info.and_then(|info| info.default_value.clone());
+        let is_unique = column_def.to_uppercase().contains("UNIQUE");
+        let is_auto_increment = is_pk && is_sqlite_rowid_alias(&data_type, pk_count);
+
+        let column_type = ColumnType {
+            base_type: data_type,
+            length: None,
+            scale: None,
+            array_dimensions: None,
+        };
+
+        columns.push(Column {
+            name: column_name,
+            column_type,
+            nullable: is_nullable,
+            default_value,
+            is_primary_key: is_pk,
+            is_foreign_key: false,
+            references: None,
+            is_unique,
+            comment: None,
+            is_auto_increment,
+        });
+    }
+
+    columns
+}
+
+/// Whether a PostgreSQL column is an identity column or draws from a sequence
+fn is_postgres_auto_increment(default_value: Option<&str>, is_identity: Option<&str>) -> bool {
+    is_identity == Some("YES")
+        || default_value.is_some_and(|default| default.trim_start().starts_with("nextval("))
+}
+
+/// Whether a MySQL `EXTRA` value marks an AUTO_INCREMENT column
+fn is_mysql_auto_increment(extra: Option<&str>) -> bool {
+    extra.is_some_and(|extra| extra.to_lowercase().contains("auto_increment"))
+}
 
-                        let column_type = ColumnType {
-                            base_type: data_type,
-                            length: None,
-                            scale: None,
-                            array_dimensions: None,
-                        };
-
-                        let column = Column {
-                            name: column_name,
-                            column_type,
-                            nullable: is_nullable,
-                            default_value: None,
-                            is_primary_key: is_pk,
-                            is_foreign_key: false,
-                            references: None,
-                            is_unique,
-                            comment: None,
-                        };
-
-                        table.add_column(column);
+/// Whether a SQLite primary key column aliases the rowid
+///
+/// Only a single-column key declared exactly `INTEGER` does.
+fn is_sqlite_rowid_alias(declared_type: &str, pk_column_count: usize) -> bool {
+    pk_column_count == 1 && declared_type.eq_ignore_ascii_case("INTEGER")
+}
+
+/// Column details of a table in a schema, via `PRAGMA table_info`
+const SQLITE_TABLE_INFO_QUERY: &str =
+    "SELECT name, \"notnull\" AS not_null, dflt_value, pk FROM pragma_table_info($1, $2) ORDER BY cid";
+
+/// Indexes of a table in a schema, via `PRAGMA index_list`
+const SQLITE_INDEX_LIST_QUERY: &str =
+    "SELECT name, \"unique\" AS is_unique, origin FROM pragma_index_list($1, $2)";
+
+/// Columns of an index in a schema, via `PRAGMA index_info`
+const SQLITE_INDEX_INFO_QUERY: &str = "SELECT name FROM pragma_index_info($1, $2) ORDER BY seqno";
+
+/// Column details reported by `PRAGMA table_info`
+struct SqliteColumnInfo {
+    name: String,
+    not_null: bool,
+    default_value: Option<String>,
+    /// 1-based position within the primary key, 0 when not part of it
+    pk_position: i64,
+}
+
+/// Read `PRAGMA table_info` for a table in the given attached schema
+async fn sqlite_column_info(
+    pool: &SqlitePool,
+    schema: &str,
+    table_name: &str,
+) -> Result<Vec<SqliteColumnInfo>> {
+    let info_query = SQLITE_TABLE_INFO_QUERY;
+    let rows = sqlx::query(info_query)
+        .bind(table_name)
+        .bind(schema)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| SchemaForgeError::db_query(info_query, e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| SqliteColumnInfo {
+            name: row.get("name"),
+            not_null: row.get::<i64, _>("not_null") != 0,
+            default_value: row.get("dflt_value"),
+            pk_position: row.get("pk"),
+        })
+        .collect())
+}
+
+/// Split the body of a CREATE TABLE statement into its comma-separated items
+///
+/// Commas inside parentheses (`DECIMAL(10,2)`, `CHECK (...)`), quoted
+/// strings and quoted identifiers do not split; `--` comments are dropped.
+fn split_column_definitions(body: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut chars = body.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' | '`' | '[' => {
+                let close = if c == '[' { ']' } else { c };
+                current.push(c);
+                while let Some(inner) = chars.next() {
+                    current.push(inner);
+                    if inner == close {
+                        // A doubled quote is an escaped quote, not the end
+                        if close != ']' && chars.peek() == Some(&close) {
+                            current.push(chars.next().unwrap());
+                            continue;
+                        }
+                        break;
                     }
                 }
             }
+            '-' if chars.peek() == Some(&'-') => {
+                for skipped in chars.by_ref() {
+                    if skipped == '\n' {
+                        current.push('\n');
+                        break;
+                    }
+                }
+            }
+            '(' => {
+                depth += 1;
+                current.push(c);
+            }
+            ')' => {
+                depth = depth.saturating_sub(1);
+                current.push(c);
+            }
+            ',' if depth == 0 => {
+                items.push(std::mem::take(&mut current));
+            }
+            _ => current.push(c),
+        }
+    }
+    items.push(current);
+
+    items
+        .into_iter()
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Split a column definition into its (unquoted) name and the remainder
+///
+/// Handles `"quoted names"`, `` `backticks` `` and `[brackets]`, which may
+/// contain spaces or commas.
+fn split_leading_identifier(definition: &str) -> (String, &str) {
+    let definition = definition.trim_start();
+    let close = match definition.chars().next() {
+        Some('"') => '"',
+        Some('`') => '`',
+        Some('[') => ']',
+        _ => {
+            let end = definition
+                .find(char::is_whitespace)
+                .unwrap_or(definition.len());
+            return (definition[..end].to_string(), &definition[end..]);
+        }
+    };
+
+    let mut name = String::new();
+    let mut chars = definition.char_indices().skip(1).peekable();
+    while let Some((index, c)) = chars.next() {
+        if c == close {
+            if close != ']' && chars.peek().map(|(_, next)| *next) == Some(close) {
+                name.push(c);
+                chars.next();
+                continue;
+            }
+            return (name, &definition[index + c.len_utf8()..]);
+        }
+        name.push(c);
+    }
+    (name, "")
+}
+
+/// Read the declared type at the start of `rest`, keeping `(10,2)`-style
+/// arguments attached
+fn leading_type(rest: &str) -> Option<String> {
+    let rest = rest.trim_start();
+    let mut depth = 0usize;
+    let end = rest
+        .char_indices()
+        .find(|(_, c)| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            c.is_whitespace() && depth == 0
+        })
+        .map_or(rest.len(), |(index, _)| index);
+    let data_type = &rest[..end];
+    (!data_type.is_empty()).then(|| data_type.to_string())
+}
+
+/// Read the indexes of a SQLite table via PRAGMA index_list/index_info
+async fn index_sqlite_indexes(
+    pool: &SqlitePool,
+    schema: &str,
+    table_name: &str,
+) -> Result<Vec<TableIndex>> {
+    let list_query = SQLITE_INDEX_LIST_QUERY;
+    let list_rows = sqlx::query(list_query)
+        .bind(table_name)
+        .bind(schema)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| SchemaForgeError::db_query(list_query, e))?;
+
+    let info_query = SQLITE_INDEX_INFO_QUERY;
+    let mut indexes = Vec::new();
+    for row in list_rows {
+        let origin: String = row.get("origin");
+        if origin == "pk" {
+            continue;
+        }
+        let name: String = row.get("name");
+        let is_unique: i64 = row.get("is_unique");
+
+        let columns = sqlx::query(info_query)
+            .bind(&name)
+            .bind(schema)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| SchemaForgeError::db_query(info_query, e))?
+            .iter()
+            .filter_map(|info| info.get::<Option<String>, _>("name"))
+            .collect();
+
+        indexes.push(TableIndex {
+            name,
+            columns,
+            is_unique: is_unique != 0,
+        });
+    }
+
+    Ok(indexes)
+}
+
+/// Index a remote libSQL/Turso database
+///
+/// Runs the same `sqlite_master` and `PRAGMA` queries as local SQLite over
+/// the libSQL client. Remote databases have no attachments, so only `main`
+/// is indexed.
+pub async fn index_libsql(connection: &LibsqlConnection) -> Result<SchemaIndex> {
+    index_libsql_with_progress(connection, IndexScope::All, &mut |_, _, _| {}).await
+}
+
+/// Index a remote libSQL/Turso database, reporting each table to `progress`
+pub async fn index_libsql_with_progress(
+    connection: &LibsqlConnection,
+    scope: IndexScope,
+    progress: IndexProgress<'_>,
+) -> Result<SchemaIndex> {
+    let mut schema_index = SchemaIndex::new();
+    schema_index.database_name = Some("main".to_string());
+    schema_index.schema_name = Some("main".to_string());
+
+    let tables_query = format!(
+        r#"
+        SELECT name, type, sql
+        FROM sqlite_master
+        WHERE type IN ('table', 'view')
+            AND name NOT LIKE 'sqlite_%'
+            {}
+        ORDER BY name
+    "#,
+        scope.catalog_filter("type", "view")
+    );
+    let (_, tables_rows) = libsql_query(connection, &tables_query, ()).await?;
+
+    let total = tables_rows.len();
+    for (position, row) in tables_rows.into_iter().enumerate() {
+        let Some(table_name) = libsql_value_text(&row[0]) else {
+            continue;
+        };
+        progress(&table_name, position + 1, total);
+        let is_view = libsql_value_text(&row[1]).as_deref() == Some("view");
+        let mut table = if is_view {
+            Table::new_view(&table_name)
+        } else {
+            Table::new(&table_name)
+        };
+
+        let (_, info_rows) = libsql_query(
+            connection,
+            SQLITE_TABLE_INFO_QUERY,
+            libsql::params![table_name.as_str(), "main"],
+        )
+        .await?;
+        let column_info: Vec<SqliteColumnInfo> = info_rows
+            .iter()
+            .map(|info| SqliteColumnInfo {
+                name: libsql_value_text(&info[0]).unwrap_or_default(),
+                not_null: libsql_value_text(&info[1]).as_deref() == Some("1"),
+                default_value: libsql_value_text(&info[2]),
+                pk_position: libsql_value_text(&info[3])
+                    .and_then(|pk| pk.parse().ok())
+                    .unwrap_or(0),
+            })
+            .collect();
+        let mut pk_columns: Vec<&SqliteColumnInfo> =
+            column_info.iter().filter(|info| info.pk_position > 0).collect();
+        pk_columns.sort_by_key(|info| info.pk_position);
+        table.primary_keys = pk_columns.iter().map(|info| info.name.clone()).collect();
+
+        if let Some(sql) = libsql_value_text(&row[2]) {
+            table.comment = Some(sql.clone());
+            for column in sqlite_columns_from_create_sql(&sql, &column_info) {
+                table.add_column(column);
+            }
+        }
+
+        if !is_view {
+            table.indexes = index_libsql_indexes(connection, &table_name).await?;
         }
 
         schema_index.add_table(table);
@@ -457,8 +978,60 @@ pub async fn index_sqlite(pool: &SqlitePool) -> Result<SchemaIndex> {
     Ok(schema_index)
 }
 
+/// Read the indexes of a libSQL table via PRAGMA index_list/index_info
+async fn index_libsql_indexes(
+    connection: &LibsqlConnection,
+    table_name: &str,
+) -> Result<Vec<TableIndex>> {
+    let (_, list_rows) = libsql_query(
+        connection,
+        SQLITE_INDEX_LIST_QUERY,
+        libsql::params![table_name, "main"],
+    )
+    .await?;
+
+    let mut indexes = Vec::new();
+    for row in list_rows {
+        if libsql_value_text(&row[2]).as_deref() == Some("pk") {
+            continue;
+        }
+        let Some(name) = libsql_value_text(&row[0]) else {
+            continue;
+        };
+        let is_unique = libsql_value_text(&row[1]).as_deref() == Some("1");
+
+        let (_, info_rows) = libsql_query(
+            connection,
+            SQLITE_INDEX_INFO_QUERY,
+            libsql::params![name.as_str(), "main"],
+        )
+        .await?;
+        let columns = info_rows
+            .iter()
+            .filter_map(|info| libsql_value_text(&info[0]))
+            .collect();
+
+        indexes.push(TableIndex {
+            name,
+            columns,
+            is_unique,
+        });
+    }
+
+    Ok(indexes)
+}
+
 /// Index Oracle database schema
 pub async fn index_oracle(connection: &OracleConnection) -> Result<SchemaIndex> {
+    index_oracle_with_progress(connection, IndexScope::All, &mut |_, _, _| {}).await
+}
+
+/// Index Oracle database schema, reporting each table to `progress`
+pub async fn index_oracle_with_progress(
+    connection: &OracleConnection,
+    scope: IndexScope,
+    progress: IndexProgress<'_>,
+) -> Result<SchemaIndex> {
     let mut schema_index = SchemaIndex::new();
 
     let context_result = connection
@@ -476,24 +1049,30 @@ pub async fn index_oracle(connection: &OracleConnection) -> Result<SchemaIndex>
         schema_index.schema_name = oracle_row_string(row, "SCHEMA_NAME");
     }
 
+    let tables_sql = "SELECT table_name AS object_name, 'TABLE' AS object_type FROM user_tables";
+    let views_sql = "SELECT view_name AS object_name, 'VIEW' AS object_type FROM user_views";
+    let objects_query = match scope {
+        IndexScope::All => format!(
+            "{} UNION ALL {} ORDER BY object_name",
+            tables_sql, views_sql
+        ),
+        IndexScope::TablesOnly => format!("{} ORDER BY object_name", tables_sql),
+        IndexScope::ViewsOnly => format!("{} ORDER BY object_name", views_sql),
+    };
     let tables_result = connection
-        .query(
-            "SELECT table_name AS object_name, 'TABLE' AS object_type FROM user_tables
-             UNION ALL
-             SELECT view_name AS object_name, 'VIEW' AS object_type FROM user_views
-             ORDER BY object_name",
-            &[],
-        )
+        .query(&objects_query, &[])
         .await
         .map_err(|e| SchemaForgeError::db_query_message("oracle table lookup", e.to_string()))?;
 
-    for row in &tables_result.rows {
+    let total = tables_result.rows.len();
+    for (position, row) in tables_result.rows.iter().enumerate() {
         let table_name = oracle_row_string(row, "OBJECT_NAME").unwrap_or_default();
         let object_type = oracle_row_string(row, "OBJECT_TYPE").unwrap_or_else(|| "TABLE".to_string());
 
         if table_name.is_empty() {
             continue;
         }
+        progress(&table_name, position + 1, total);
 
         let mut table = if object_type.eq_ignore_ascii_case("VIEW") {
             Table::new_view(&table_name)
@@ -545,6 +1124,7 @@ pub async fn index_oracle(connection: &OracleConnection) -> Result<SchemaIndex>
                 references: None,
                 is_unique: false,
                 comment: None,
+                is_auto_increment: false,
             });
         }
 
@@ -620,12 +1200,56 @@ pub async fn index_oracle(connection: &OracleConnection) -> Result<SchemaIndex>
             });
         }
 
+        let index_result = connection
+            .query(
+                "SELECT ic.index_name,
+                        ic.column_name,
+                        i.uniqueness
+                 FROM user_ind_columns ic
+                 JOIN user_indexes i
+                   ON i.index_name = ic.index_name
+                 WHERE ic.table_name = :1
+                   AND NOT EXISTS (
+                       SELECT 1 FROM user_constraints c
+                       WHERE c.index_name = ic.index_name
+                         AND c.constraint_type = 'P'
+                   )
+                 ORDER BY ic.index_name, ic.column_position",
+                &[table_name.clone().into()],
+            )
+            .await
+            .map_err(|e| SchemaForgeError::db_query_message("oracle index lookup", e.to_string()))?;
+
+        table.indexes = group_index_columns(index_result.rows.iter().map(|index_row| {
+            (
+                oracle_row_string(index_row, "INDEX_NAME").unwrap_or_default(),
+                oracle_row_string(index_row, "COLUMN_NAME").unwrap_or_default(),
+                oracle_row_string(index_row, "UNIQUENESS").as_deref() == Some("UNIQUE"),
+            )
+        }));
+
         schema_index.add_table(table);
     }
 
     Ok(schema_index)
 }
 
+/// Group (index, column, unique) rows ordered by index name into indexes
+fn group_index_columns(rows: impl IntoIterator<Item = (String, String, bool)>) -> Vec<TableIndex> {
+    let mut indexes: Vec<TableIndex> = Vec::new();
+    for (name, column, is_unique) in rows {
+        match indexes.last_mut() {
+            Some(index) if index.name == name => index.columns.push(column),
+            _ => indexes.push(TableIndex {
+                name,
+                columns: vec![column],
+                is_unique,
+            }),
+        }
+    }
+    indexes
+}
+
 fn oracle_row_string(row: &oracle_rs::Row, column_name: &str) -> Option<String> {
     row.get_by_name(column_name)
         .map(|value| value.to_string())
@@ -668,4 +1292,101 @@ mod tests {
         // Basic test to verify module compiles
         assert!(true);
     }
+
+    #[test]
+    fn test_postgres_auto_increment_detection() {
+        assert!(is_postgres_auto_increment(
+            Some("nextval('users_id_seq'::regclass)"),
+            Some("NO")
+        ));
+        assert!(is_postgres_auto_increment(None, Some("YES")));
+        assert!(!is_postgres_auto_increment(Some("0"), Some("NO")));
+    }
+
+    #[test]
+    fn test_mysql_auto_increment_detection() {
+        assert!(is_mysql_auto_increment(Some("auto_increment")));
+        assert!(is_mysql_auto_increment(Some("AUTO_INCREMENT")));
+        assert!(!is_mysql_auto_increment(Some("DEFAULT_GENERATED")));
+        assert!(!is_mysql_auto_increment(None));
+    }
+
+    #[test]
+    fn test_sqlite_rowid_alias_detection() {
+        assert!(is_sqlite_rowid_alias("INTEGER", 1));
+        assert!(is_sqlite_rowid_alias("integer", 1));
+        assert!(!is_sqlite_rowid_alias("INT", 1));
+        assert!(!is_sqlite_rowid_alias("INTEGER", 2));
+    }
+
+    #[test]
+    fn test_split_column_definitions_respects_parentheses() {
+        let items = split_column_definitions(
+            "id INTEGER PRIMARY KEY, amount DECIMAL(10,2) NOT NULL, CHECK (amount > 0 AND id IN (1, 2)), UNIQUE (id, amount)",
+        );
+        assert_eq!(
+            items,
+            vec![
+                "id INTEGER PRIMARY KEY",
+                "amount DECIMAL(10,2) NOT NULL",
+                "CHECK (amount > 0 AND id IN (1, 2))",
+                "UNIQUE (id, amount)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sqlite_columns_from_create_sql() {
+        let info = vec![
+            SqliteColumnInfo {
+                name: "id".to_string(),
+                not_null: false,
+                default_value: None,
+                pk_position: 1,
+            },
+            SqliteColumnInfo {
+                name: "email".to_string(),
+                not_null: true,
+                default_value: Some("''".to_string()),
+                pk_position: 0,
+            },
+        ];
+        let columns = sqlite_columns_from_create_sql(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT NOT NULL UNIQUE, UNIQUE (email))",
+            &info,
+        );
+
+        assert_eq!(columns.len(), 2);
+        assert!(columns[0].is_primary_key && columns[0].is_auto_increment);
+        assert_eq!(columns[1].column_type.base_type, "TEXT");
+        assert!(!columns[1].nullable && columns[1].is_unique);
+        assert_eq!(columns[1].default_value.as_deref(), Some("''"));
+    }
+
+    #[test]
+    fn test_split_column_definitions_ignores_commas_in_strings() {
+        let items = split_column_definitions(
+            "label TEXT DEFAULT 'a, b', \"odd, name\" TEXT, note TEXT DEFAULT 'it''s, fine' -- trailing, comment\n",
+        );
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0], "label TEXT DEFAULT 'a, b'");
+        assert_eq!(items[1], "\"odd, name\" TEXT");
+        assert_eq!(items[2], "note TEXT DEFAULT 'it''s, fine'");
+        assert_eq!(split_leading_identifier(&items[1]), ("odd, name".to_string(), " TEXT"));
+        assert_eq!(split_leading_identifier("[amount] REAL").0, "amount");
+        assert_eq!(leading_type(" DECIMAL(10, 2) NOT NULL").as_deref(), Some("DECIMAL(10, 2)"));
+        assert_eq!(leading_type(""), None);
+    }
+
+    #[test]
+    fn test_group_index_columns() {
+        let indexes = group_index_columns(vec![
+            ("idx_a".to_string(), "x".to_string(), false),
+            ("idx_a".to_string(), "y".to_string(), false),
+            ("idx_b".to_string(), "z".to_string(), true),
+        ]);
+        assert_eq!(indexes.len(), 2);
+        assert_eq!(indexes[0].columns, vec!["x", "y"]);
+        assert!(indexes[1].is_unique);
+    }
 }
@@ -7,6 +7,9 @@ pub mod cache;
 pub mod connection;
 pub mod indexer;
 pub mod manager;
+pub mod results;
 pub mod schema;
+pub mod sql;
+pub mod templates;
 
 // Re-exports
@@ -0,0 +1,725 @@
+//! Query Results
+//!
+//! This module holds buffered query results so they can be rendered with
+//! display limits and inspected again after the table has been printed.
+
+use crate::database::schema::TypeCategory;
+use crate::error::{Result, SchemaForgeError};
+use comfy_table::{CellAlignment, Table};
+
+/// Marker appended to truncated cells
+const ELLIPSIS: char = '…';
+
+/// How drivers store a NULL cell in [`QueryResult::rows`]
+pub const NULL_CELL: &str = "NULL";
+
+/// File formats a result can be exported as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// GitHub-flavored markdown table
+    Markdown,
+    /// Comma-separated values with a header row
+    Csv,
+    /// JSON array with one object per row
+    Json,
+}
+
+impl ExportFormat {
+    /// Human-readable name of the format
+    pub fn name(self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "markdown",
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Json => "JSON",
+        }
+    }
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = SchemaForgeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "md" | "markdown" => Ok(ExportFormat::Markdown),
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            other => Err(SchemaForgeError::InvalidInput(format!(
+                "Unknown export format '{}'. Use md, csv or json.",
+                other
+            ))),
+        }
+    }
+}
+
+/// Buffered result of a single SQL statement
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryResult {
+    /// Column names, in result order
+    pub columns: Vec<String>,
+    /// Row values rendered as strings (NULL stored as [`NULL_CELL`])
+    pub rows: Vec<Vec<String>>,
+    /// Status message for statements that return no rows
+    pub message: Option<String>,
+    /// Row cap that stopped the fetch early, if one did
+    pub truncated_at: Option<usize>,
+    /// Category of each column's driver type; empty when the driver gave none
+    pub column_types: Vec<TypeCategory>,
+}
+
+impl QueryResult {
+    /// Create a result for a statement that returns no rows
+    pub fn from_message(message: impl Into<String>) -> Self {
+        Self {
+            message: Some(message.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Render the result as a table, truncating cells longer than `cell_width`
+    pub fn render(&self, cell_width: Option<usize>) -> String {
+        self.render_with_null(cell_width, NULL_CELL)
+    }
+
+    /// [`QueryResult::render`] showing NULL cells as `null_display`
+    pub fn render_with_null(&self, cell_width: Option<usize>, null_display: &str) -> String {
+        self.render_with_grouping(cell_width, null_display, false)
+    }
+
+    /// [`QueryResult::render_with_null`], writing numeric cells with comma
+    /// thousands separators when `number_grouping` is set
+    pub fn render_with_grouping(
+        &self,
+        cell_width: Option<usize>,
+        null_display: &str,
+        number_grouping: bool,
+    ) -> String {
+        if let Some(ref message) = self.message {
+            return message.clone();
+        }
+        if self.rows.is_empty() {
+            return "No results found.".to_string();
+        }
+
+        let grouped = if number_grouping {
+            self.numeric_typed_columns()
+        } else {
+            vec![false; self.columns.len()]
+        };
+        let mut table = Table::new();
+        table.set_header(&self.columns);
+        for row in &self.rows {
+            table.add_row(
+                row.iter()
+                    .enumerate()
+                    .map(|(index, value)| {
+                        if value == NULL_CELL {
+                            null_display.to_string()
+                        } else if grouped.get(index).copied().unwrap_or(false) {
+                            group_thousands(value)
+                        } else {
+                            value.clone()
+                        }
+                    })
+                    .map(|value| match cell_width {
+                        Some(width) => truncate_cell(&value, width),
+                        None => value,
+                    })
+                    .collect::<Vec<_>>(),
+            );
+        }
+
+        // Numbers line up on the right, as in psql and mysql; the header follows
+        for (index, numeric) in self.numeric_columns().into_iter().enumerate() {
+            if let (true, Some(column)) = (numeric, table.column_mut(index)) {
+                column.set_cell_alignment(CellAlignment::Right);
+            }
+        }
+
+        match self.truncated_at {
+            Some(limit) => format!("{}\n(truncated at {} rows)", table, limit),
+            None => format!("{}", table),
+        }
+    }
+
+    /// Which columns hold only numbers, ignoring NULLs
+    ///
+    /// A column of nothing but NULLs is not numeric.
+    pub fn numeric_columns(&self) -> Vec<bool> {
+        (0..self.columns.len())
+            .map(|index| {
+                let mut values = self
+                    .rows
+                    .iter()
+                    .filter_map(|row| row.get(index))
+                    .filter(|value| value.as_str() != NULL_CELL)
+                    .peekable();
+                values.peek().is_some() && values.all(|value| is_numeric_text(value))
+            })
+            .collect()
+    }
+
+    /// Which columns have a numeric driver type
+    ///
+    /// Results without driver types (libSQL, Oracle, streamed batches) have
+    /// none: text that looks numeric, such as a zip code or phone number,
+    /// must not be grouped.
+    fn numeric_typed_columns(&self) -> Vec<bool> {
+        if self.column_types.len() != self.columns.len() {
+            return vec![false; self.columns.len()];
+        }
+        self.column_types
+            .iter()
+            .map(|category| *category == TypeCategory::Numeric)
+            .collect()
+    }
+
+    /// Render the result in an export format, without truncating cells
+    ///
+    /// NULL cells are written as `null_display`, except in JSON, where they
+    /// are always `null`.
+    pub fn export(&self, format: ExportFormat, null_display: &str) -> String {
+        match format {
+            ExportFormat::Markdown => self.to_markdown(null_display),
+            ExportFormat::Csv => self.to_csv(null_display),
+            ExportFormat::Json => self.to_json(),
+        }
+    }
+
+    /// Render the result as a GitHub-flavored markdown table
+    pub fn to_markdown(&self, null_display: &str) -> String {
+        let mut lines = Vec::with_capacity(self.rows.len() + 2);
+        lines.push(markdown_row(&self.columns));
+        lines.push(format!("|{}", " --- |".repeat(self.columns.len())));
+        lines.extend(
+            self.rows
+                .iter()
+                .map(|row| markdown_row(&display_nulls(row, null_display))),
+        );
+        lines.join("\n") + "\n"
+    }
+
+    /// Render the result as CSV with a header row
+    pub fn to_csv(&self, null_display: &str) -> String {
+        std::iter::once(self.columns.clone())
+            .chain(self.rows.iter().map(|row| display_nulls(row, null_display)))
+            .map(|row| row.iter().map(|value| csv_field(value)).collect::<Vec<_>>().join(","))
+            .map(|line| line + "\n")
+            .collect()
+    }
+
+    /// Render the result as a JSON array of objects keyed by column name
+    ///
+    /// NULL cells become `null`; every other value stays a string, as shown.
+    pub fn to_json(&self) -> String {
+        let rows: Vec<serde_json::Value> = self
+            .rows
+            .iter()
+            .map(|row| {
+                serde_json::Value::Object(
+                    self.columns
+                        .iter()
+                        .zip(row)
+                        .map(|(column, value)| {
+                            let value = if value == NULL_CELL {
+                                serde_json::Value::Null
+                            } else {
+                                serde_json::Value::String(value.clone())
+                            };
+                            (column.clone(), value)
+                        })
+                        .collect(),
+                )
+            })
+            .collect();
+        serde_json::to_string_pretty(&rows).unwrap_or_default() + "\n"
+    }
+
+    /// Position of a column given by name or 1-based index
+    pub fn column_index(&self, column: &str) -> Option<usize> {
+        match column.parse::<usize>() {
+            Ok(index) => index.checked_sub(1).filter(|index| *index < self.columns.len()),
+            Err(_) => self
+                .columns
+                .iter()
+                .position(|name| name.eq_ignore_ascii_case(column)),
+        }
+    }
+
+    /// Get a cell by 1-based row number and column name or 1-based index
+    pub fn cell(&self, row: usize, column: &str) -> Option<&str> {
+        let column_index = self.column_index(column)?;
+
+        self.rows
+            .get(row.checked_sub(1)?)?
+            .get(column_index)
+            .map(|value| value.as_str())
+    }
+
+    /// Keep only the rows with a cell accepted by `matches`
+    ///
+    /// With `column` set, only that column is checked; `None` is returned
+    /// when it does not exist.
+    pub fn filter_rows(&self, column: Option<&str>, matches: impl Fn(&str) -> bool) -> Option<Self> {
+        let column_index = match column {
+            Some(column) => Some(self.column_index(column)?),
+            None => None,
+        };
+
+        let rows = self
+            .rows
+            .iter()
+            .filter(|row| match column_index {
+                Some(index) => row.get(index).is_some_and(|value| matches(value)),
+                None => row.iter().any(|value| matches(value)),
+            })
+            .cloned()
+            .collect();
+        Some(Self {
+            columns: self.columns.clone(),
+            rows,
+            message: None,
+            truncated_at: self.truncated_at,
+            column_types: self.column_types.clone(),
+        })
+    }
+
+    /// Compare this result with an earlier run of the same query
+    ///
+    /// Rows are matched on the `key` columns when they are unique in both
+    /// results; otherwise only whole rows are compared, so a changed row
+    /// shows up as removed and added.
+    pub fn diff(&self, previous: &QueryResult, key: &[usize]) -> ResultDiff {
+        let key_of = |row: &Vec<String>| -> Vec<String> {
+            key.iter().map(|index| row.get(*index).cloned().unwrap_or_default()).collect()
+        };
+        let unique_keys = |rows: &[Vec<String>]| {
+            let keys: std::collections::HashSet<Vec<String>> = rows.iter().map(key_of).collect();
+            keys.len() == rows.len()
+        };
+
+        let mut diff = ResultDiff::default();
+        if !key.is_empty() && unique_keys(&self.rows) && unique_keys(&previous.rows) {
+            let before: std::collections::HashMap<Vec<String>, &Vec<String>> =
+                previous.rows.iter().map(|row| (key_of(row), row)).collect();
+            let after: std::collections::HashSet<Vec<String>> =
+                self.rows.iter().map(key_of).collect();
+            for row in &self.rows {
+                match before.get(&key_of(row)) {
+                    Some(old) if *old != row => diff.changed.push(((*old).clone(), row.clone())),
+                    Some(_) => {}
+                    None => diff.added.push(row.clone()),
+                }
+            }
+            diff.removed = previous
+                .rows
+                .iter()
+                .filter(|row| !after.contains(&key_of(row)))
+                .cloned()
+                .collect();
+            return diff;
+        }
+
+        let mut unmatched: std::collections::HashMap<&Vec<String>, usize> =
+            std::collections::HashMap::new();
+        for row in &previous.rows {
+            *unmatched.entry(row).or_default() += 1;
+        }
+        for row in &self.rows {
+            match unmatched.get_mut(row) {
+                Some(count) if *count > 0 => *count -= 1,
+                _ => diff.added.push(row.clone()),
+            }
+        }
+        for row in &previous.rows {
+            if let Some(count) = unmatched.get_mut(row).filter(|count| **count > 0) {
+                *count -= 1;
+                diff.removed.push(row.clone());
+            }
+        }
+        diff
+    }
+}
+
+/// Rows that differ between two runs of the same query
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResultDiff {
+    /// Rows only in the new result
+    pub added: Vec<Vec<String>>,
+    /// Rows only in the earlier result
+    pub removed: Vec<Vec<String>>,
+    /// `(before, after)` pairs sharing a key but differing elsewhere
+    pub changed: Vec<(Vec<String>, Vec<String>)>,
+}
+
+impl ResultDiff {
+    /// Check whether the two results hold the same rows
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// One line per row: `+` added, `-` removed, `~` changed (before -> after)
+    pub fn render(&self, null_display: &str) -> String {
+        let cells = |row: &[String]| {
+            row.iter()
+                .map(|value| if value == NULL_CELL { null_display } else { value.as_str() })
+                .collect::<Vec<_>>()
+                .join(" | ")
+        };
+        let mut lines = Vec::new();
+        lines.extend(self.added.iter().map(|row| format!("+ {}", cells(row))));
+        lines.extend(
+            self.changed
+                .iter()
+                .map(|(before, after)| format!("~ {} -> {}", cells(before), cells(after))),
+        );
+        lines.extend(self.removed.iter().map(|row| format!("- {}", cells(row))));
+        lines.join("\n")
+    }
+}
+
+/// Check whether a rendered cell is a plain number like `-12`, `3.50` or `1e6`
+fn is_numeric_text(value: &str) -> bool {
+    value.chars().any(|ch| ch.is_ascii_digit())
+        && value
+            .chars()
+            .all(|ch| ch.is_ascii_digit() || matches!(ch, '-' | '+' | '.' | 'e' | 'E'))
+        && value.parse::<f64>().is_ok()
+}
+
+/// Write a plain number with commas between groups of three integer digits
+///
+/// `-1234567.891` becomes `-1,234,567.891`. Anything that is not a plain
+/// decimal number, including exponent notation, is returned unchanged.
+fn group_thousands(value: &str) -> String {
+    if !is_numeric_text(value) || value.contains(['e', 'E']) {
+        return value.to_string();
+    }
+    let (sign, unsigned) = match value.strip_prefix(['-', '+']) {
+        Some(rest) => (&value[..1], rest),
+        None => ("", value),
+    };
+    let (integer, fraction) = match unsigned.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (unsigned, None),
+    };
+
+    let mut grouped = String::with_capacity(value.len() + integer.len() / 3);
+    grouped.push_str(sign);
+    for (position, digit) in integer.chars().enumerate() {
+        if position > 0 && (integer.len() - position) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        grouped.push('.');
+        grouped.push_str(fraction);
+    }
+    grouped
+}
+
+/// Check whether a driver type name denotes raw binary data
+pub fn is_binary_type(type_name: &str) -> bool {
+    matches!(
+        type_name.to_uppercase().as_str(),
+        "BYTEA" | "BLOB" | "TINYBLOB" | "MEDIUMBLOB" | "LONGBLOB" | "BINARY" | "VARBINARY"
+    )
+}
+
+/// Render binary data as `\x<hex>`, showing at most `max_bytes` bytes
+///
+/// Longer values end with "…" and the total size, so the output is always
+/// valid UTF-8 however the bytes are encoded.
+pub fn format_binary(bytes: &[u8], max_bytes: usize) -> String {
+    let shown = &bytes[..bytes.len().min(max_bytes)];
+    let mut output = String::with_capacity(2 + shown.len() * 2);
+    output.push_str("\\x");
+    for byte in shown {
+        output.push_str(&format!("{:02x}", byte));
+    }
+    if bytes.len() > max_bytes {
+        output.push(ELLIPSIS);
+        output.push_str(&format!(" ({} bytes total)", bytes.len()));
+    }
+    output
+}
+
+/// A row with NULL cells replaced by `null_display`
+fn display_nulls(row: &[String], null_display: &str) -> Vec<String> {
+    row.iter()
+        .map(|value| {
+            if value == NULL_CELL {
+                null_display.to_string()
+            } else {
+                value.clone()
+            }
+        })
+        .collect()
+}
+
+/// Format one markdown table row, escaping pipes and line breaks
+fn markdown_row(values: &[String]) -> String {
+    let cells: Vec<String> = values
+        .iter()
+        .map(|value| {
+            value
+                .replace('\\', "\\\\")
+                .replace('|', "\\|")
+                .replace("\r\n", "<br>")
+                .replace('\n', "<br>")
+        })
+        .collect();
+    format!("| {} |", cells.join(" | "))
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Truncate a cell to at most `width` characters, ending with "…" when cut
+pub fn truncate_cell(value: &str, width: usize) -> String {
+    if value.chars().count() <= width {
+        return value.to_string();
+    }
+
+    let mut truncated: String = value.chars().take(width.saturating_sub(1)).collect();
+    truncated.push(ELLIPSIS);
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_result() -> QueryResult {
+        QueryResult {
+            columns: vec!["id".to_string(), "payload".to_string()],
+            rows: vec![vec!["1".to_string(), "x".repeat(100)]],
+            message: None,
+            truncated_at: None,
+            column_types: Vec::new(),
+        }
+    }
+
+    fn snapshot(rows: &[[&str; 2]]) -> QueryResult {
+        QueryResult {
+            columns: vec!["id".to_string(), "name".to_string()],
+            rows: rows
+                .iter()
+                .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+                .collect(),
+            message: None,
+            truncated_at: None,
+            column_types: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_diff_classifies_added_and_changed_rows() {
+        let before = snapshot(&[["1", "Alice"], ["2", "Bob"], ["3", "Carol"]]);
+        let after = snapshot(&[["1", "Alice"], ["2", "Robert"], ["3", "Carol"], ["4", "Dana"]]);
+
+        let diff = after.diff(&before, &[0]);
+        assert_eq!(diff.added, vec![vec!["4".to_string(), "Dana".to_string()]]);
+        assert_eq!(
+            diff.changed,
+            vec![(
+                vec!["2".to_string(), "Bob".to_string()],
+                vec!["2".to_string(), "Robert".to_string()]
+            )]
+        );
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.render("NULL"), "+ 4 | Dana\n~ 2 | Bob -> 2 | Robert");
+
+        // Without a key the changed row is a removal plus an addition
+        let diff = after.diff(&before, &[]);
+        assert!(diff.changed.is_empty());
+        assert_eq!(diff.added.len(), 2);
+        assert_eq!(diff.removed, vec![vec!["2".to_string(), "Bob".to_string()]]);
+
+        assert!(after.diff(&after, &[0]).is_empty());
+    }
+
+    #[test]
+    fn test_truncate_cell() {
+        assert_eq!(truncate_cell("short", 10), "short");
+        assert_eq!(truncate_cell("abcdefghij", 5), "abcd…");
+        assert_eq!(truncate_cell("ééééé", 3).chars().count(), 3);
+    }
+
+    #[test]
+    fn test_render_truncates_long_cells() {
+        let rendered = sample_result().render(Some(10));
+        assert!(rendered.contains(&format!("{}…", "x".repeat(9))));
+        assert!(!rendered.contains(&"x".repeat(10)));
+
+        let rendered = sample_result().render(None);
+        assert!(rendered.contains(&"x".repeat(100)));
+    }
+
+    #[test]
+    fn test_render_right_aligns_numeric_columns() {
+        let result = QueryResult {
+            columns: vec!["name".to_string(), "n".to_string(), "score".to_string()],
+            rows: vec![
+                vec!["Alice".to_string(), "10".to_string(), "1.5".to_string()],
+                vec!["Bo".to_string(), "5".to_string(), "NULL".to_string()],
+            ],
+            message: None,
+            truncated_at: None,
+            column_types: Vec::new(),
+        };
+        assert_eq!(result.numeric_columns(), vec![false, true, true]);
+
+        let rendered = result.render(None);
+        // Text is padded on the right, numbers and their header on the left
+        assert!(rendered.contains("| name  |  n | score |"), "{}", rendered);
+        assert!(rendered.contains("| Bo    |  5 |  NULL |"), "{}", rendered);
+        assert!(rendered.contains("| Alice | 10 |   1.5 |"), "{}", rendered);
+    }
+
+    #[test]
+    fn test_numeric_columns_need_a_number() {
+        let result = QueryResult {
+            columns: vec!["empty".to_string(), "code".to_string()],
+            rows: vec![vec!["NULL".to_string(), "1e".to_string()]],
+            message: None,
+            truncated_at: None,
+            column_types: Vec::new(),
+        };
+        assert_eq!(result.numeric_columns(), vec![false, false]);
+    }
+
+    #[test]
+    fn test_markdown_export_escapes_pipes() {
+        let result = QueryResult {
+            columns: vec!["id".to_string(), "expression".to_string()],
+            rows: vec![
+                vec!["1".to_string(), "a | b".to_string()],
+                vec!["2".to_string(), "line one\nline two".to_string()],
+            ],
+            message: None,
+            truncated_at: None,
+            column_types: Vec::new(),
+        };
+
+        assert_eq!(
+            result.export(ExportFormat::Markdown, NULL_CELL),
+            "| id | expression |\n| --- | --- |\n| 1 | a \\| b |\n| 2 | line one<br>line two |\n"
+        );
+        assert_eq!(
+            result.export(ExportFormat::Csv, NULL_CELL),
+            "id,expression\n1,a | b\n2,\"line one\nline two\"\n"
+        );
+        assert_eq!("md".parse::<ExportFormat>().unwrap(), ExportFormat::Markdown);
+        assert!("xlsx".parse::<ExportFormat>().is_err());
+    }
+
+    #[test]
+    fn test_number_grouping() {
+        let result = QueryResult {
+            columns: vec!["zip".to_string(), "total".to_string(), "ratio".to_string()],
+            rows: vec![vec!["90210".to_string(), "-1234567".to_string(), "12345.678".to_string()]],
+            message: None,
+            truncated_at: None,
+            column_types: vec![TypeCategory::Text, TypeCategory::Numeric, TypeCategory::Numeric],
+        };
+
+        let rendered = result.render_with_grouping(None, NULL_CELL, true);
+        assert!(rendered.contains("| 90210 | -1,234,567 | 12,345.678 |"), "{}", rendered);
+        assert!(result.render_with_null(None, NULL_CELL).contains("-1234567"));
+
+        let json = result.export(ExportFormat::Json, NULL_CELL);
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed[0]["total"], "-1234567");
+        assert_eq!(result.export(ExportFormat::Csv, NULL_CELL), "zip,total,ratio\n90210,-1234567,12345.678\n");
+
+        // Without driver types nothing is grouped, however numeric it looks
+        let untyped = QueryResult {
+            column_types: Vec::new(),
+            ..result.clone()
+        };
+        let rendered = untyped.render_with_grouping(None, NULL_CELL, true);
+        assert!(rendered.contains("| 90210 | -1234567 | 12345.678 |"), "{}", rendered);
+
+        assert_eq!(group_thousands("999"), "999");
+        assert_eq!(group_thousands("1000"), "1,000");
+        assert_eq!(group_thousands("+123456.5"), "+123,456.5");
+        assert_eq!(group_thousands("1e10"), "1e10");
+        assert_eq!(group_thousands("N/A"), "N/A");
+    }
+
+    #[test]
+    fn test_null_display() {
+        let result = QueryResult {
+            columns: vec!["name".to_string(), "city".to_string()],
+            rows: vec![vec!["Ada".to_string(), NULL_CELL.to_string()]],
+            message: None,
+            truncated_at: None,
+            column_types: Vec::new(),
+        };
+
+        let rendered = result.render_with_null(None, "(null)");
+        assert!(rendered.contains("| Ada  | (null) |"), "{}", rendered);
+        assert!(!rendered.contains("NULL"));
+        assert_eq!(result.export(ExportFormat::Csv, ""), "name,city\nAda,\n");
+
+        let json = result.export(ExportFormat::Json, "(null)");
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed[0]["name"], "Ada");
+        assert!(parsed[0]["city"].is_null());
+        assert_eq!("json".parse::<ExportFormat>().unwrap(), ExportFormat::Json);
+    }
+
+    #[test]
+    fn test_format_binary_short_and_long() {
+        assert_eq!(format_binary(&[0xde, 0xad, 0xbe, 0xef], 32), "\\xdeadbeef");
+
+        let long: Vec<u8> = (0..=255).collect();
+        let formatted = format_binary(&long, 4);
+        assert!(formatted.starts_with("\\x00010203…"));
+        assert!(formatted.ends_with("(256 bytes total)"));
+
+        assert!(is_binary_type("bytea"));
+        assert!(is_binary_type("BLOB"));
+        assert!(!is_binary_type("TEXT"));
+    }
+
+    #[test]
+    fn test_cell_lookup() {
+        let result = sample_result();
+        assert_eq!(result.cell(1, "payload").map(str::len), Some(100));
+        assert_eq!(result.cell(1, "1"), Some("1"));
+        assert_eq!(result.cell(2, "id"), None);
+        assert_eq!(result.cell(1, "0"), None);
+        assert_eq!(result.cell(1, "missing"), None);
+    }
+
+    #[test]
+    fn test_filter_rows() {
+        let result = QueryResult {
+            columns: vec!["name".to_string(), "city".to_string()],
+            rows: vec![
+                vec!["Ada".to_string(), "London".to_string()],
+                vec!["Linus".to_string(), "Helsinki".to_string()],
+                vec!["London Breed".to_string(), "San Francisco".to_string()],
+            ],
+            message: None,
+            truncated_at: None,
+            column_types: Vec::new(),
+        };
+
+        let any_column = result.filter_rows(None, |value| value.contains("London")).unwrap();
+        assert_eq!(any_column.rows.len(), 2);
+
+        let by_city = result.filter_rows(Some("CITY"), |value| value.contains("London")).unwrap();
+        assert_eq!(by_city.rows, vec![vec!["Ada".to_string(), "London".to_string()]]);
+
+        assert!(result.filter_rows(Some("country"), |_| true).is_none());
+    }
+}
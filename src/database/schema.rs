@@ -3,9 +3,13 @@
 //! This module defines the core data structures for representing
 //! database schema information, including tables, columns, and their metadata.
 
+use crate::config::storage::Config;
+use crate::database::connection::DatabaseBackend;
+use crate::error::{Result, SchemaForgeError};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::fmt;
+use std::path::{Path, PathBuf};
 
 /// Represents the type of a database column
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -20,6 +24,211 @@ pub struct ColumnType {
     pub array_dimensions: Option<u32>,
 }
 
+impl ColumnType {
+    /// Split a PostgreSQL array type into its element type and dimensions
+    ///
+    /// `integer[][]` becomes `("integer", Some(2))`; a type without a `[]`
+    /// suffix is returned unchanged with `None`.
+    pub fn split_array_type(type_name: &str) -> (&str, Option<u32>) {
+        let mut element = type_name.trim();
+        let mut dimensions = 0;
+        while let Some(inner) = element.strip_suffix("[]") {
+            element = inner.trim_end();
+            dimensions += 1;
+        }
+        (element, (dimensions > 0).then_some(dimensions))
+    }
+
+    /// Broad category of this type on `backend`
+    ///
+    /// Arrays and unrecognised types are `Other`. MySQL's `tinyint(1)` is
+    /// its boolean.
+    pub fn category(&self, backend: DatabaseBackend) -> TypeCategory {
+        if self.array_dimensions.is_some() {
+            return TypeCategory::Other;
+        }
+
+        let lower = self.base_type.trim().to_lowercase();
+        let (name, inline_length) = match lower.split_once('(') {
+            Some((name, args)) => (
+                name.trim(),
+                args.split([',', ')']).next().and_then(|len| len.trim().parse::<i64>().ok()),
+            ),
+            None => (lower.as_str(), None),
+        };
+        let name = name
+            .trim_end_matches(" zerofill")
+            .trim_end_matches(" unsigned")
+            .trim_end_matches(" signed");
+
+        if backend == DatabaseBackend::MySQL
+            && name == "tinyint"
+            && inline_length.or(self.length) == Some(1)
+        {
+            return TypeCategory::Boolean;
+        }
+
+        let backend_types = match backend {
+            DatabaseBackend::PostgreSQL => POSTGRES_TYPE_CATEGORIES,
+            DatabaseBackend::MySQL => MYSQL_TYPE_CATEGORIES,
+            DatabaseBackend::SQLite => SQLITE_TYPE_CATEGORIES,
+            DatabaseBackend::Oracle => ORACLE_TYPE_CATEGORIES,
+            DatabaseBackend::MSSQL => MSSQL_TYPE_CATEGORIES,
+        };
+        if let Some((_, category)) = backend_types
+            .iter()
+            .chain(COMMON_TYPE_CATEGORIES)
+            .find(|(type_name, _)| *type_name == name)
+        {
+            return *category;
+        }
+
+        // SQLite accepts any declared type and picks an affinity from it
+        if backend == DatabaseBackend::SQLite {
+            if name.contains("int") || ["real", "floa", "doub", "dec", "num"].iter().any(|part| name.contains(part)) {
+                return TypeCategory::Numeric;
+            }
+            if ["char", "clob", "text"].iter().any(|part| name.contains(part)) {
+                return TypeCategory::Text;
+            }
+        }
+        TypeCategory::Other
+    }
+
+    /// Check whether this type holds numbers on `backend`
+    pub fn is_numeric(&self, backend: DatabaseBackend) -> bool {
+        self.category(backend) == TypeCategory::Numeric
+    }
+
+    /// Check whether this type holds dates, times or intervals on `backend`
+    pub fn is_temporal(&self, backend: DatabaseBackend) -> bool {
+        self.category(backend) == TypeCategory::Temporal
+    }
+
+    /// Check whether this type holds character data on `backend`
+    pub fn is_text(&self, backend: DatabaseBackend) -> bool {
+        self.category(backend) == TypeCategory::Text
+    }
+}
+
+/// Broad kind of value a column type holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeCategory {
+    Numeric,
+    Temporal,
+    Text,
+    Boolean,
+    Binary,
+    Json,
+    Other,
+}
+
+/// Type names that mean the same on every backend
+const COMMON_TYPE_CATEGORIES: &[(&str, TypeCategory)] = &[
+    ("int", TypeCategory::Numeric),
+    ("integer", TypeCategory::Numeric),
+    ("smallint", TypeCategory::Numeric),
+    ("bigint", TypeCategory::Numeric),
+    ("decimal", TypeCategory::Numeric),
+    ("numeric", TypeCategory::Numeric),
+    ("real", TypeCategory::Numeric),
+    ("float", TypeCategory::Numeric),
+    ("double precision", TypeCategory::Numeric),
+    ("date", TypeCategory::Temporal),
+    ("time", TypeCategory::Temporal),
+    ("timestamp", TypeCategory::Temporal),
+    ("char", TypeCategory::Text),
+    ("character", TypeCategory::Text),
+    ("varchar", TypeCategory::Text),
+    ("character varying", TypeCategory::Text),
+    ("text", TypeCategory::Text),
+    ("boolean", TypeCategory::Boolean),
+    ("json", TypeCategory::Json),
+];
+
+const POSTGRES_TYPE_CATEGORIES: &[(&str, TypeCategory)] = &[
+    ("int2", TypeCategory::Numeric),
+    ("int4", TypeCategory::Numeric),
+    ("int8", TypeCategory::Numeric),
+    ("float4", TypeCategory::Numeric),
+    ("float8", TypeCategory::Numeric),
+    ("serial", TypeCategory::Numeric),
+    ("bigserial", TypeCategory::Numeric),
+    ("smallserial", TypeCategory::Numeric),
+    ("money", TypeCategory::Numeric),
+    ("timestamptz", TypeCategory::Temporal),
+    ("timestamp with time zone", TypeCategory::Temporal),
+    ("timestamp without time zone", TypeCategory::Temporal),
+    ("timetz", TypeCategory::Temporal),
+    ("time with time zone", TypeCategory::Temporal),
+    ("time without time zone", TypeCategory::Temporal),
+    ("interval", TypeCategory::Temporal),
+    ("bpchar", TypeCategory::Text),
+    ("citext", TypeCategory::Text),
+    ("name", TypeCategory::Text),
+    ("uuid", TypeCategory::Text),
+    ("bool", TypeCategory::Boolean),
+    ("bytea", TypeCategory::Binary),
+    ("jsonb", TypeCategory::Json),
+];
+
+const MYSQL_TYPE_CATEGORIES: &[(&str, TypeCategory)] = &[
+    ("tinyint", TypeCategory::Numeric),
+    ("mediumint", TypeCategory::Numeric),
+    ("double", TypeCategory::Numeric),
+    ("bit", TypeCategory::Numeric),
+    ("year", TypeCategory::Numeric),
+    ("datetime", TypeCategory::Temporal),
+    ("tinytext", TypeCategory::Text),
+    ("mediumtext", TypeCategory::Text),
+    ("longtext", TypeCategory::Text),
+    ("enum", TypeCategory::Text),
+    ("set", TypeCategory::Text),
+    ("bool", TypeCategory::Boolean),
+    ("binary", TypeCategory::Binary),
+    ("varbinary", TypeCategory::Binary),
+    ("blob", TypeCategory::Binary),
+    ("tinyblob", TypeCategory::Binary),
+    ("mediumblob", TypeCategory::Binary),
+    ("longblob", TypeCategory::Binary),
+];
+
+const SQLITE_TYPE_CATEGORIES: &[(&str, TypeCategory)] = &[
+    ("datetime", TypeCategory::Temporal),
+    ("blob", TypeCategory::Binary),
+];
+
+const ORACLE_TYPE_CATEGORIES: &[(&str, TypeCategory)] = &[
+    ("number", TypeCategory::Numeric),
+    ("binary_float", TypeCategory::Numeric),
+    ("binary_double", TypeCategory::Numeric),
+    ("varchar2", TypeCategory::Text),
+    ("nvarchar2", TypeCategory::Text),
+    ("nchar", TypeCategory::Text),
+    ("clob", TypeCategory::Text),
+    ("nclob", TypeCategory::Text),
+    ("blob", TypeCategory::Binary),
+    ("raw", TypeCategory::Binary),
+];
+
+const MSSQL_TYPE_CATEGORIES: &[(&str, TypeCategory)] = &[
+    ("tinyint", TypeCategory::Numeric),
+    ("money", TypeCategory::Numeric),
+    ("smallmoney", TypeCategory::Numeric),
+    ("datetime", TypeCategory::Temporal),
+    ("datetime2", TypeCategory::Temporal),
+    ("smalldatetime", TypeCategory::Temporal),
+    ("datetimeoffset", TypeCategory::Temporal),
+    ("nchar", TypeCategory::Text),
+    ("nvarchar", TypeCategory::Text),
+    ("ntext", TypeCategory::Text),
+    ("uniqueidentifier", TypeCategory::Text),
+    ("bit", TypeCategory::Boolean),
+    ("binary", TypeCategory::Binary),
+    ("varbinary", TypeCategory::Binary),
+    ("image", TypeCategory::Binary),
+];
+
 impl fmt::Display for ColumnType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.base_type)?;
@@ -40,7 +249,7 @@ impl fmt::Display for ColumnType {
 }
 
 /// Represents a column in a database table
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Column {
     /// Column name
     pub name: String,
@@ -60,6 +269,9 @@ pub struct Column {
     pub is_unique: bool,
     /// Column comment (if any)
     pub comment: Option<String>,
+    /// Whether the database generates values (identity, serial, AUTO_INCREMENT, rowid alias)
+    #[serde(default)]
+    pub is_auto_increment: bool,
 }
 
 impl fmt::Display for Column {
@@ -69,6 +281,9 @@ impl fmt::Display for Column {
         if self.is_primary_key {
             write!(f, " PRIMARY KEY")?;
         }
+        if self.is_auto_increment {
+            write!(f, " AUTO_INCREMENT")?;
+        }
         if self.is_foreign_key {
             write!(f, " FOREIGN KEY")?;
         }
@@ -90,7 +305,7 @@ impl fmt::Display for Column {
 }
 
 /// Foreign key reference information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ForeignKeyReference {
     /// Referenced table name
     pub table: String,
@@ -102,11 +317,45 @@ pub struct ForeignKeyReference {
     pub on_update: Option<String>,
 }
 
+/// Secondary index defined on a table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableIndex {
+    /// Index name
+    pub name: String,
+    /// Indexed columns (ordered)
+    pub columns: Vec<String>,
+    /// Whether this is a unique index
+    pub is_unique: bool,
+}
+
+/// Order in which a table's columns are listed for display
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnOrder {
+    /// Definition order, as indexed
+    #[default]
+    Ordinal,
+    /// Case-insensitive alphabetical order
+    Alpha,
+}
+
+impl fmt::Display for ColumnOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnOrder::Ordinal => write!(f, "ordinal"),
+            ColumnOrder::Alpha => write!(f, "alpha"),
+        }
+    }
+}
+
 /// Represents a database table or view
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Table {
     /// Table or view name
     pub name: String,
+    /// Owning schema, when the index spans more than one
+    #[serde(default)]
+    pub schema: Option<String>,
     /// Whether this is a view (vs a table)
     pub is_view: bool,
     /// Table columns
@@ -119,6 +368,18 @@ pub struct Table {
     pub comment: Option<String>,
     /// Estimated row count (if available)
     pub estimated_rows: Option<i64>,
+    /// Indexes defined on the table
+    #[serde(default)]
+    pub indexes: Vec<TableIndex>,
+    /// Top-level keys of a sampled value, per JSON column (values are not kept)
+    #[serde(default)]
+    pub json_keys: BTreeMap<String, Vec<String>>,
+    /// Whether this is a declarative partition (vs a table that inherits)
+    #[serde(default)]
+    pub is_partition: bool,
+    /// Parent table of a partition or inheriting table
+    #[serde(default)]
+    pub partition_of: Option<String>,
 }
 
 impl Table {
@@ -126,12 +387,17 @@ impl Table {
     pub fn new(name: impl Into<String>) -> Self {
         Self {
             name: name.into(),
+            schema: None,
             is_view: false,
             columns: Vec::new(),
             primary_keys: Vec::new(),
             foreign_keys: Vec::new(),
             comment: None,
             estimated_rows: None,
+            indexes: Vec::new(),
+            json_keys: BTreeMap::new(),
+            is_partition: false,
+            partition_of: None,
         }
     }
 
@@ -142,6 +408,14 @@ impl Table {
         table
     }
 
+    /// Name qualified with the schema (`schema.table`) when one is set
+    pub fn qualified_name(&self) -> String {
+        match self.schema {
+            Some(ref schema) => format!("{}.{}", schema, self.name),
+            None => self.name.clone(),
+        }
+    }
+
     /// Add a column to the table
     pub fn add_column(&mut self, column: Column) {
         self.columns.push(column);
@@ -152,14 +426,81 @@ impl Table {
         self.columns.iter().find(|c| c.name == name)
     }
 
+    /// Check whether an index (or the primary key) leads with this column
+    pub fn has_leading_index(&self, column: &str) -> bool {
+        self.primary_keys.first().map(|k| k.as_str()) == Some(column)
+            || self
+                .indexes
+                .iter()
+                .any(|index| index.columns.first().map(|c| c.as_str()) == Some(column))
+    }
+
+    /// Columns in the requested display order
+    ///
+    /// The stored column list always stays in definition order.
+    pub fn ordered_columns(&self, order: ColumnOrder) -> Vec<&Column> {
+        let mut columns: Vec<&Column> = self.columns.iter().collect();
+        if order == ColumnOrder::Alpha {
+            columns.sort_by_cached_key(|column| column.name.to_lowercase());
+        }
+        columns
+    }
+
+    /// Describe the parent of a partition or inheriting table
+    ///
+    /// For example "users_2024 is a partition of users".
+    pub fn partition_note(&self) -> Option<String> {
+        let parent = self.partition_of.as_ref()?;
+        let relation = if self.is_partition {
+            "is a partition of"
+        } else {
+            "inherits from"
+        };
+        Some(format!("{} {} {}", self.qualified_name(), relation, parent))
+    }
+
+    /// Format the table as one compact line: `name (col: type, [PK] id: int, ...)`
+    pub fn format_summary(&self) -> String {
+        self.format_summary_ordered(ColumnOrder::Ordinal)
+    }
+
+    /// [`Table::format_summary`] with columns in `order`
+    pub fn format_summary_ordered(&self, order: ColumnOrder) -> String {
+        let prefix = if self.is_view { "[VIEW] " } else { "" };
+        let column_info: Vec<String> = self
+            .ordered_columns(order)
+            .into_iter()
+            .map(|c| {
+                let mut info = format!("{}: {}", c.name, c.column_type.base_type);
+                if c.is_primary_key {
+                    info = format!("[PK] {}", info);
+                }
+                if c.is_foreign_key {
+                    info = format!("[FK] {}", info);
+                }
+                info
+            })
+            .collect();
+
+        format!("{}{} ({})", prefix, self.qualified_name(), column_info.join(", "))
+    }
+
     /// Format table schema for display
     pub fn format_schema(&self) -> String {
+        self.format_schema_ordered(ColumnOrder::Ordinal)
+    }
+
+    /// [`Table::format_schema`] with columns in `order`
+    pub fn format_schema_ordered(&self, order: ColumnOrder) -> String {
         let prefix = if self.is_view { "View" } else { "Table" };
-        let mut result = format!("{}: {}\n", prefix, self.name);
+        let mut result = format!("{}: {}\n", prefix, self.qualified_name());
 
         if let Some(ref comment) = self.comment {
             result.push_str(&format!("  -- {}\n", comment));
         }
+        if let Some(note) = self.partition_note() {
+            result.push_str(&format!("  -- {}\n", note));
+        }
 
         if !self.primary_keys.is_empty() {
             result.push_str(&format!("  Primary Key: {}\n", self.primary_keys.join(", ")));
@@ -176,14 +517,194 @@ impl Table {
         }
 
         result.push_str("  Columns:\n");
-        for column in &self.columns {
-            result.push_str(&format!("    {}\n", column));
+        for column in self.ordered_columns(order) {
+            match self.json_keys.get(&column.name) {
+                Some(keys) => result.push_str(&format!(
+                    "    {} {{keys: {}}}\n",
+                    column,
+                    keys.join(", ")
+                )),
+                None => result.push_str(&format!("    {}\n", column)),
+            }
         }
 
         result
     }
 }
 
+impl Table {
+    /// Generate a `CREATE TABLE` statement for this table
+    ///
+    /// Identifiers are quoted and column types renamed for the target
+    /// backend. Views are not reconstructed since their definitions are not
+    /// indexed; a comment is returned instead.
+    pub fn to_create_statement(&self, backend: DatabaseBackend) -> String {
+        if self.is_view {
+            return format!(
+                "-- {} is a view; its definition is not captured in the schema index",
+                quote_identifier(&self.name, backend)
+            );
+        }
+
+        let mut lines: Vec<String> = self
+            .columns
+            .iter()
+            .map(|column| {
+                let mut line = format!(
+                    "{} {}",
+                    quote_identifier(&column.name, backend),
+                    ddl_type_name(&column.column_type, backend)
+                );
+                if !column.nullable {
+                    line.push_str(" NOT NULL");
+                }
+                if let Some(ref default) = column.default_value {
+                    line.push_str(&format!(" DEFAULT {}", default));
+                }
+                if column.is_unique && !column.is_primary_key {
+                    line.push_str(" UNIQUE");
+                }
+                line
+            })
+            .collect();
+
+        let primary_keys: Vec<&str> = if self.primary_keys.is_empty() {
+            self.columns
+                .iter()
+                .filter(|c| c.is_primary_key)
+                .map(|c| c.name.as_str())
+                .collect()
+        } else {
+            self.primary_keys.iter().map(|k| k.as_str()).collect()
+        };
+        if !primary_keys.is_empty() {
+            lines.push(format!(
+                "PRIMARY KEY ({})",
+                primary_keys
+                    .iter()
+                    .map(|k| quote_identifier(k, backend))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        for column in &self.columns {
+            if let Some(ref fk) = column.references {
+                let mut line = format!(
+                    "FOREIGN KEY ({}) REFERENCES {} ({})",
+                    quote_identifier(&column.name, backend),
+                    quote_identifier(&fk.table, backend),
+                    quote_identifier(&fk.column, backend)
+                );
+                if let Some(ref action) = fk.on_delete {
+                    line.push_str(&format!(" ON DELETE {}", action));
+                }
+                if let Some(ref action) = fk.on_update {
+                    line.push_str(&format!(" ON UPDATE {}", action));
+                }
+                lines.push(line);
+            }
+        }
+
+        let table_name = match self.schema {
+            Some(ref schema) => format!(
+                "{}.{}",
+                quote_identifier(schema, backend),
+                quote_identifier(&self.name, backend)
+            ),
+            None => quote_identifier(&self.name, backend),
+        };
+        format!(
+            "CREATE TABLE {} (\n    {}\n);",
+            table_name,
+            lines.join(",\n    ")
+        )
+    }
+}
+
+/// Quote an identifier using the backend's delimiter style
+pub fn quote_identifier(name: &str, backend: DatabaseBackend) -> String {
+    match backend {
+        DatabaseBackend::MySQL => format!("`{}`", name.replace('`', "``")),
+        DatabaseBackend::MSSQL => format!("[{}]", name.replace(']', "]]")),
+        DatabaseBackend::PostgreSQL | DatabaseBackend::SQLite | DatabaseBackend::Oracle => {
+            format!("\"{}\"", name.replace('"', "\"\""))
+        }
+    }
+}
+
+/// Check whether a column type holds JSON documents
+pub fn is_json_type(base_type: &str) -> bool {
+    matches!(base_type.to_lowercase().as_str(), "json" | "jsonb")
+}
+
+/// Top-level key names of a JSON object, or `None` if `value` is not one
+pub fn json_object_keys(value: &str) -> Option<Vec<String>> {
+    match serde_json::from_str::<serde_json::Value>(value).ok()? {
+        serde_json::Value::Object(map) => Some(map.keys().cloned().collect()),
+        _ => None,
+    }
+}
+
+/// Fold the unquoted parts of a possibly schema-qualified name
+///
+/// Double-quoted parts keep their case and lose their quotes.
+fn fold_identifier(name: &str, fold: fn(&str) -> String) -> String {
+    name.split('.')
+        .map(|part| match part.strip_prefix('"').and_then(|p| p.strip_suffix('"')) {
+            Some(quoted) => quoted.replace("\"\"", "\""),
+            None => fold(part),
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Render a column type using the target backend's type names
+fn ddl_type_name(column_type: &ColumnType, backend: DatabaseBackend) -> String {
+    let base = column_type.base_type.to_lowercase();
+    let mapped = match (backend, base.as_str()) {
+        (DatabaseBackend::MySQL, "boolean" | "bool") => "TINYINT(1)".to_string(),
+        (DatabaseBackend::MySQL, "character varying") => "VARCHAR".to_string(),
+        (DatabaseBackend::MySQL, "bytea") => "BLOB".to_string(),
+        (DatabaseBackend::MySQL, "timestamp with time zone" | "timestamptz") => "TIMESTAMP".to_string(),
+        (DatabaseBackend::MSSQL, "boolean" | "bool") => "BIT".to_string(),
+        (DatabaseBackend::MSSQL, "text") => "NVARCHAR(MAX)".to_string(),
+        (DatabaseBackend::MSSQL, "character varying" | "varchar") => "NVARCHAR".to_string(),
+        (DatabaseBackend::MSSQL, "timestamp" | "timestamp without time zone") => "DATETIME2".to_string(),
+        (DatabaseBackend::Oracle, "boolean" | "bool") => "NUMBER(1)".to_string(),
+        (DatabaseBackend::Oracle, "text") => "CLOB".to_string(),
+        (DatabaseBackend::Oracle, "character varying" | "varchar") => "VARCHAR2".to_string(),
+        (DatabaseBackend::PostgreSQL, "tinyint(1)") => "boolean".to_string(),
+        _ => column_type.base_type.clone(),
+    };
+
+    let mut result = mapped;
+    // Integer-like types report a numeric precision that is not valid DDL,
+    // so only emit a length for types that accept one.
+    if !result.contains('(') && type_accepts_length(&base) {
+        if let Some(len) = column_type.length {
+            result.push_str(&format!("({}", len));
+            if let Some(scale) = column_type.scale {
+                result.push_str(&format!(", {}", scale));
+            }
+            result.push(')');
+        }
+    }
+    if backend == DatabaseBackend::PostgreSQL {
+        for _ in 0..column_type.array_dimensions.unwrap_or(0) {
+            result.push_str("[]");
+        }
+    }
+    result
+}
+
+/// Check whether a type name takes a length/precision argument
+fn type_accepts_length(base_type: &str) -> bool {
+    ["char", "numeric", "decimal", "binary", "number"]
+        .iter()
+        .any(|fragment| base_type.contains(fragment))
+}
+
 impl fmt::Display for Table {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.format_schema())
@@ -191,16 +712,20 @@ impl fmt::Display for Table {
 }
 
 /// Complete database schema index
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SchemaIndex {
     /// Database name (if available)
     pub database_name: Option<String>,
     /// Schema/namespace name (e.g., "public", "dbo")
     pub schema_name: Option<String>,
-    /// Tables and views indexed by name
+    /// Tables and views keyed by qualified name (`schema.table` when the
+    /// table carries a schema, otherwise the bare name)
     pub tables: BTreeMap<String, Table>,
     /// Relationships between tables
     pub relationships: Vec<TableRelationship>,
+    /// Tables left out of the index, each with the reason
+    #[serde(default)]
+    pub skipped_tables: Vec<String>,
     /// Index timestamp
     pub indexed_at: chrono::DateTime<chrono::Utc>,
 }
@@ -213,19 +738,133 @@ impl SchemaIndex {
             schema_name: None,
             tables: BTreeMap::new(),
             relationships: Vec::new(),
+            skipped_tables: Vec::new(),
             indexed_at: chrono::Utc::now(),
         }
     }
 
     /// Add a table to the index
     pub fn add_table(&mut self, table: Table) {
-        let name = table.name.clone();
+        let name = table.qualified_name();
         self.tables.insert(name, table);
     }
 
-    /// Get a table by name
+    /// Fold another index (another schema or attached database) into this one
+    ///
+    /// Tables are keyed by qualified name, so tables from different schemas
+    /// sit side by side; a table with the same qualified name is replaced.
+    /// Relationships are appended, the earliest `indexed_at` is kept, and
+    /// this index's database and schema names win when set.
+    pub fn merge(&mut self, other: SchemaIndex) {
+        self.tables.extend(other.tables);
+        self.relationships.extend(other.relationships);
+        self.skipped_tables.extend(other.skipped_tables);
+        self.indexed_at = self.indexed_at.min(other.indexed_at);
+        if self.database_name.is_none() {
+            self.database_name = other.database_name;
+        }
+        if self.schema_name.is_none() {
+            self.schema_name = other.schema_name;
+        }
+    }
+
+    /// Get a table by qualified (`schema.table`) or bare name
+    ///
+    /// A bare name resolves to the table in the current schema first, then
+    /// to a table without a schema, then to the only table with that name.
+    /// Bare names that match tables in several other schemas return `None`.
     pub fn get_table(&self, name: &str) -> Option<&Table> {
-        self.tables.get(name)
+        if let Some(table) = self.tables.get(name) {
+            return Some(table);
+        }
+
+        if let Some((schema, table_name)) = name.split_once('.') {
+            let in_current_schema = self.schema_name.as_deref() == Some(schema);
+            return self.tables.values().find(|table| {
+                table.name == table_name
+                    && match table.schema {
+                        Some(ref table_schema) => table_schema == schema,
+                        None => in_current_schema,
+                    }
+            });
+        }
+
+        if let Some(ref current) = self.schema_name {
+            if let Some(table) = self.tables.get(&format!("{}.{}", current, name)) {
+                return Some(table);
+            }
+        }
+
+        let mut matches = self.tables.values().filter(|table| table.name == name);
+        match (matches.next(), matches.next()) {
+            (Some(table), None) => Some(table),
+            _ => None,
+        }
+    }
+
+    /// Look up a table the way the backend resolves a name typed by the user
+    ///
+    /// An exact match always wins. Beyond that, SQLite and SQL Server match
+    /// names case-insensitively, PostgreSQL folds unquoted parts to lowercase,
+    /// Oracle folds them to uppercase, and MySQL requires an exact match.
+    pub fn get_table_for_backend(&self, name: &str, backend: DatabaseBackend) -> Option<&Table> {
+        if let Some(table) = self.get_table(name) {
+            return Some(table);
+        }
+
+        match backend {
+            DatabaseBackend::SQLite | DatabaseBackend::MSSQL => {
+                let wanted = name.to_lowercase();
+                let mut matches = self.tables.iter().filter_map(|(key, table)| {
+                    (key.to_lowercase() == wanted || table.name.to_lowercase() == wanted)
+                        .then_some(table)
+                });
+                match (matches.next(), matches.next()) {
+                    (Some(table), None) => Some(table),
+                    _ => None,
+                }
+            }
+            DatabaseBackend::PostgreSQL => self.get_table(&fold_identifier(name, str::to_lowercase)),
+            DatabaseBackend::Oracle => self.get_table(&fold_identifier(name, str::to_uppercase)),
+            DatabaseBackend::MySQL => None,
+        }
+    }
+
+    /// Foreign key columns with no index leading on them, as (table, column)
+    ///
+    /// Only meaningful when the indexer captured indexes for the backend.
+    pub fn unindexed_foreign_keys(&self) -> Vec<(String, String)> {
+        self.tables
+            .values()
+            .filter(|table| !table.is_view)
+            .flat_map(|table| {
+                table
+                    .columns
+                    .iter()
+                    .filter(|column| column.is_foreign_key && !table.has_leading_index(&column.name))
+                    .map(|column| (table.qualified_name(), column.name.clone()))
+            })
+            .collect()
+    }
+
+    /// Counts of tables, views, columns and relationships in the index
+    pub fn stats(&self) -> SchemaStats {
+        SchemaStats {
+            table_count: self.tables.values().filter(|table| !table.is_view).count(),
+            view_count: self.tables.values().filter(|table| table.is_view).count(),
+            column_count: self.tables.values().map(|table| table.columns.len()).sum(),
+            relationship_count: self.relationships.len(),
+            tables_without_pk: self
+                .tables
+                .values()
+                .filter(|table| {
+                    !table.is_view
+                        && table.primary_keys.is_empty()
+                        && !table.columns.iter().any(|column| column.is_primary_key)
+                })
+                .map(|table| table.qualified_name())
+                .collect(),
+        }
     }
 
     /// Get all table names
@@ -283,24 +922,69 @@ impl SchemaIndex {
             result.push_str("\n");
         }
 
-        // Relationships section
-        if !self.relationships.is_empty() {
-            result.push_str("Relationships:\n");
-            for rel in &self.relationships {
-                result.push_str(&format!(
-                    "  {}.{} -> {}.{} ({})\n",
-                    rel.from_table, rel.from_column, rel.to_table, rel.to_column, rel.relationship_type
-                ));
+        result.push_str(&format_relationships(&self.relationships, true));
+
+        result
+    }
+
+    /// Format the schema for LLM context within a character budget
+    ///
+    /// Tables are included whole, in name order, until the next one would
+    /// exceed `max_chars`; a note lists how many tables were left out so the
+    /// LLM never sees a definition cut off mid-table.
+    pub fn format_for_llm_within(&self, max_chars: usize) -> String {
+        self.format_tables_within(max_chars).0
+    }
+
+    /// [`format_for_llm_within`](Self::format_for_llm_within), also naming
+    /// the tables that fit
+    pub fn format_tables_within(&self, max_chars: usize) -> (String, Vec<&str>) {
+        let full = self.format_for_llm();
+        if full.len() <= max_chars {
+            return (full, self.tables.keys().map(String::as_str).collect());
+        }
+
+        let mut result = String::new();
+        if let Some(ref db_name) = self.database_name {
+            result.push_str(&format!("Database: {}\n", db_name));
+        }
+        if let Some(ref schema) = self.schema_name {
+            result.push_str(&format!("Schema: {}\n", schema));
+        }
+        result.push('\n');
+
+        let mut included = Vec::new();
+        for (name, table) in &self.tables {
+            let block = format!("{}\n", table.format_schema());
+            if result.len() + block.len() > max_chars {
+                break;
             }
+            result.push_str(&block);
+            included.push(name.as_str());
         }
 
-        result
+        let omitted = self.tables.len() - included.len();
+        if omitted > 0 {
+            result.push_str(&format!(
+                "({} of {} tables omitted to fit the context budget)\n",
+                omitted,
+                self.tables.len()
+            ));
+        }
+
+        let relationships = self.relationships.iter().filter(|rel| {
+            included.contains(&rel.from_table.as_str()) && included.contains(&rel.to_table.as_str())
+        });
+        result.push_str(&format_relationships(relationships, true));
+
+        (result, included)
     }
 
     /// Generate a concise schema summary for LLM
     ///
     /// This provides a more compact view focusing on table names and
-    /// their relationships, useful when token count is limited.
+    /// their relationships, useful when token count is limited. Partitions
+    /// whose parent is indexed are listed by name under the parent.
     pub fn format_summary_for_llm(&self) -> String {
         let mut result = String::new();
 
@@ -309,43 +993,110 @@ impl SchemaIndex {
         }
 
         result.push_str("\nTables:\n");
-        for (name, table) in &self.tables {
-            let prefix = if table.is_view { "[VIEW] " } else { "" };
-            result.push_str(&format!("  {}{} (", prefix, name));
+        for table in self.tables.values() {
+            if table.is_partition && self.partition_parent(table).is_some() {
+                continue;
+            }
+            result.push_str(&format!("  {}\n", table.format_summary()));
+            let partitions = self.partitions_of(table);
+            if !partitions.is_empty() {
+                let names: Vec<String> = partitions.iter().map(|p| p.qualified_name()).collect();
+                result.push_str(&format!("    partitions: {}\n", names.join(", ")));
+            }
+        }
 
-            // List column names with types
-            let column_info: Vec<String> = table
-                .columns
-                .iter()
-                .map(|c| {
-                    let mut info = format!("{}: {}", c.name, c.column_type.base_type);
-                    if c.is_primary_key {
-                        info = format!("[PK] {}", info);
-                    }
-                    if c.is_foreign_key {
-                        info = format!("[FK] {}", info);
-                    }
-                    info
-                })
-                .collect();
+        if !self.relationships.is_empty() {
+            result.push('\n');
+            result.push_str(&format_relationships(&self.relationships, false));
+        }
 
-            result.push_str(&column_info.join(", "));
-            result.push_str(")\n");
+        result
+    }
+
+    /// Table names and the relationship graph, without any columns
+    ///
+    /// For questions about how tables connect, where column lists would
+    /// only spend tokens.
+    pub fn format_relationships_for_llm(&self) -> String {
+        let mut result = String::new();
+
+        if let Some(ref db_name) = self.database_name {
+            result.push_str(&format!("Database: {}\n", db_name));
         }
 
-        if !self.relationships.is_empty() {
-            result.push_str("\nRelationships:\n");
-            for rel in &self.relationships {
-                result.push_str(&format!(
-                    "  {}.{} -> {}.{}\n",
-                    rel.from_table, rel.from_column, rel.to_table, rel.to_column
-                ));
-            }
+        result.push_str("\nTables:\n");
+        for table in self.tables.values() {
+            let kind = if table.is_view { " (view)" } else { "" };
+            result.push_str(&format!("  {}{}\n", table.qualified_name(), kind));
+        }
+
+        result.push('\n');
+        if self.relationships.is_empty() {
+            result.push_str("No foreign key relationships are indexed.\n");
+        } else {
+            result.push_str(&format_relationships(&self.relationships, true));
         }
 
         result
     }
 
+    /// Indexed parent of a partition or inheriting table
+    pub fn partition_parent(&self, table: &Table) -> Option<&Table> {
+        let parent = table.partition_of.as_deref()?;
+        self.tables
+            .values()
+            .find(|candidate| candidate.name == parent && candidate.schema == table.schema)
+    }
+
+    /// Declarative partitions of `parent`, in name order
+    pub fn partitions_of(&self, parent: &Table) -> Vec<&Table> {
+        self.tables
+            .values()
+            .filter(|table| {
+                table.is_partition
+                    && table.schema == parent.schema
+                    && table.partition_of.as_deref() == Some(parent.name.as_str())
+            })
+            .collect()
+    }
+
+    /// Note for the LLM listing the date/time, boolean and JSON columns of
+    /// the tables named in `tables`
+    ///
+    /// These are where models most often forget dialect-specific casts and
+    /// literals. `None` when no listed column falls into those categories.
+    pub fn type_coercion_note(&self, backend: DatabaseBackend, tables: &[&str]) -> Option<String> {
+        let lines: Vec<String> = self
+            .tables
+            .iter()
+            .filter(|(name, _)| tables.contains(&name.as_str()))
+            .filter_map(|(_, table)| {
+                let columns: Vec<String> = table
+                    .columns
+                    .iter()
+                    .filter(|column| {
+                        matches!(
+                            column.column_type.category(backend),
+                            TypeCategory::Temporal | TypeCategory::Boolean | TypeCategory::Json
+                        )
+                    })
+                    .map(|column| format!("{} {}", column.name, column.column_type.base_type))
+                    .collect();
+                (!columns.is_empty())
+                    .then(|| format!("  {}: {}", table.qualified_name(), columns.join(", ")))
+            })
+            .collect();
+        if lines.is_empty() {
+            return None;
+        }
+
+        Some(format!(
+            "Date/time, boolean and JSON columns (compare them using {} literals and casts):\n{}",
+            backend,
+            lines.join("\n")
+        ))
+    }
+
     /// Search tables by column name
     pub fn find_tables_with_column(&self, column_name: &str) -> Vec<&Table> {
         self.tables
@@ -362,6 +1113,185 @@ impl SchemaIndex {
             .filter(|t| t.name.to_lowercase().contains(&pattern_lower))
             .collect()
     }
+
+    /// Copy of the index limited to the `max_tables` tables most relevant to
+    /// `query`, along with the number of tables left out
+    ///
+    /// Ties keep name order. Relationships are kept only between retained
+    /// tables.
+    pub fn retain_relevant_tables(&self, query: &str, max_tables: usize) -> (SchemaIndex, usize) {
+        if self.tables.len() <= max_tables {
+            return (self.clone(), 0);
+        }
+
+        let terms = query_terms(query);
+        let mut ranked: Vec<(&String, usize)> = self
+            .tables
+            .iter()
+            .map(|(key, table)| (key, relevance_score(table, &terms)))
+            .collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1));
+
+        let mut limited = self.clone();
+        limited.tables = ranked
+            .into_iter()
+            .take(max_tables)
+            .map(|(key, _)| (key.clone(), self.tables[key].clone()))
+            .collect();
+        limited.relationships.retain(|rel| {
+            limited.get_table(&rel.from_table).is_some() && limited.get_table(&rel.to_table).is_some()
+        });
+
+        (limited, self.tables.len() - max_tables)
+    }
+
+    /// Drop tables that would only clutter the LLM context
+    ///
+    /// Tables whose columns could not be read (some foreign or system
+    /// tables report none) are always dropped; catalog tables are dropped
+    /// unless `include_system_tables` is set. Each one is recorded in
+    /// [`SchemaIndex::skipped_tables`], along with relationships touching it.
+    pub fn drop_unusable_tables(&mut self, include_system_tables: bool) {
+        let mut dropped = Vec::new();
+        self.tables.retain(|name, table| {
+            let reason = if table.columns.is_empty() {
+                "no columns"
+            } else if !include_system_tables && is_system_table_name(name) {
+                "system table"
+            } else {
+                return true;
+            };
+            dropped.push((name.clone(), table.name.clone(), reason));
+            false
+        });
+
+        for (qualified, name, reason) in dropped {
+            self.relationships.retain(|rel| {
+                ![&rel.from_table, &rel.to_table]
+                    .iter()
+                    .any(|table| **table == qualified || **table == name)
+            });
+            self.skipped_tables.push(format!("{} ({})", qualified, reason));
+        }
+    }
+
+    /// Default directory for `/backup-schema` snapshots: `snapshots` in the
+    /// config directory
+    pub fn default_snapshot_dir() -> Result<PathBuf> {
+        Ok(Config::config_dir()?.join("snapshots"))
+    }
+
+    /// Write the index as JSON to `schema-YYYYMMDD-HHMMSS.json` in `dir`
+    ///
+    /// The directory is created if needed. An existing snapshot is never
+    /// overwritten: a second one in the same second gets a `-2` suffix, and
+    /// so on. Returns the written path.
+    pub fn save_snapshot(&self, dir: &Path) -> Result<PathBuf> {
+        std::fs::create_dir_all(dir)?;
+        let json = serde_json::to_string_pretty(self)?;
+        let stem = format!("schema-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+        for attempt in 1.. {
+            let path = match attempt {
+                1 => dir.join(format!("{}.json", stem)),
+                n => dir.join(format!("{}-{}.json", stem, n)),
+            };
+            match std::fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    std::io::Write::write_all(&mut file, json.as_bytes())?;
+                    return Ok(path);
+                }
+                Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(error) => return Err(error.into()),
+            }
+        }
+        unreachable!("every snapshot name is taken")
+    }
+
+    /// Read an index written by [`SchemaIndex::save_snapshot`]
+    pub fn load_snapshot(path: &Path) -> Result<Self> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+}
+
+/// Schemas that hold only database catalogs
+pub const SYSTEM_SCHEMAS: &[&str] = &[
+    "information_schema",
+    "pg_catalog",
+    "pg_toast",
+    "sys",
+    "mysql",
+    "performance_schema",
+];
+
+/// Check whether `name`, optionally schema-qualified, is a catalog table
+pub fn is_system_table_name(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    let (schema, table) = lower.rsplit_once('.').unwrap_or(("", lower.as_str()));
+    SYSTEM_SCHEMAS.contains(&schema)
+        || table.starts_with("pg_")
+        || table.starts_with("sqlite_")
+        || table == "dual"
+}
+
+/// "Relationships:" section with one `from.column -> to.column` line per
+/// relationship, followed by its type when `with_type` is set; empty when
+/// there are none
+fn format_relationships<'a>(
+    relationships: impl IntoIterator<Item = &'a TableRelationship>,
+    with_type: bool,
+) -> String {
+    let lines: Vec<String> = relationships
+        .into_iter()
+        .map(|rel| {
+            let edge = format!("{}.{} -> {}.{}", rel.from_table, rel.from_column, rel.to_table, rel.to_column);
+            if with_type {
+                format!("  {} ({})\n", edge, rel.relationship_type)
+            } else {
+                format!("  {}\n", edge)
+            }
+        })
+        .collect();
+    if lines.is_empty() {
+        return String::new();
+    }
+    format!("Relationships:\n{}", lines.concat())
+}
+
+/// Lower-cased words of a query, with a trailing plural "s" also dropped
+fn query_terms(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| word.len() >= 3)
+        .flat_map(|word| {
+            let word = word.to_lowercase();
+            let singular = word.strip_suffix('s').map(str::to_string);
+            std::iter::once(word).chain(singular)
+        })
+        .collect()
+}
+
+/// Score a table against query terms: name matches weigh most, then
+/// partial name matches, then column name matches
+fn relevance_score(table: &Table, terms: &[String]) -> usize {
+    let name = table.name.to_lowercase();
+    let singular = name.strip_suffix('s').unwrap_or(&name);
+    terms
+        .iter()
+        .map(|term| {
+            let mut score = 0;
+            if name == *term || singular == term {
+                score += 10;
+            } else if name.contains(term.as_str()) {
+                score += 3;
+            }
+            score
+                + 2 * table
+                    .columns
+                    .iter()
+                    .filter(|column| column.name.eq_ignore_ascii_case(term))
+                    .count()
+        })
+        .sum()
 }
 
 impl Default for SchemaIndex {
@@ -376,8 +1306,41 @@ impl fmt::Display for SchemaIndex {
     }
 }
 
+/// Summary counts for a schema index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaStats {
+    /// Number of tables, excluding views
+    pub table_count: usize,
+    /// Number of views
+    pub view_count: usize,
+    /// Number of columns across tables and views
+    pub column_count: usize,
+    /// Number of foreign key relationships
+    pub relationship_count: usize,
+    /// Tables with no primary key, by qualified name
+    pub tables_without_pk: Vec<String>,
+}
+
+impl fmt::Display for SchemaStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Tables: {}", self.table_count)?;
+        writeln!(f, "Views: {}", self.view_count)?;
+        writeln!(f, "Columns: {}", self.column_count)?;
+        write!(f, "Relationships: {}", self.relationship_count)?;
+        if !self.tables_without_pk.is_empty() {
+            write!(
+                f,
+                "\nTables without a primary key ({}): {}",
+                self.tables_without_pk.len(),
+                self.tables_without_pk.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
 /// Represents a relationship between two tables
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TableRelationship {
     /// Source table
     pub from_table: String,
@@ -414,6 +1377,69 @@ mod tests {
         assert_eq!(col_type.to_string(), "integer");
     }
 
+    #[test]
+    fn test_split_array_type() {
+        assert_eq!(ColumnType::split_array_type("integer[]"), ("integer", Some(1)));
+        assert_eq!(ColumnType::split_array_type("text[][]"), ("text", Some(2)));
+        assert_eq!(ColumnType::split_array_type("text"), ("text", None));
+    }
+
+    fn type_named(base_type: &str) -> ColumnType {
+        ColumnType {
+            base_type: base_type.to_string(),
+            length: None,
+            scale: None,
+            array_dimensions: None,
+        }
+    }
+
+    #[test]
+    fn test_column_type_category() {
+        let pg = DatabaseBackend::PostgreSQL;
+        assert!(type_named("int4").is_numeric(pg));
+        assert!(type_named("varchar").is_text(pg));
+        assert!(type_named("timestamptz").is_temporal(pg));
+        assert_eq!(type_named("jsonb").category(pg), TypeCategory::Json);
+        assert_eq!(type_named("jsonb").category(DatabaseBackend::MySQL), TypeCategory::Other);
+
+        let mysql = DatabaseBackend::MySQL;
+        assert_eq!(type_named("tinyint(1)").category(mysql), TypeCategory::Boolean);
+        assert!(type_named("tinyint(4)").is_numeric(mysql));
+        assert!(type_named("int unsigned").is_numeric(mysql));
+        assert_eq!(type_named("blob").category(mysql), TypeCategory::Binary);
+        assert_eq!(type_named("blob").category(DatabaseBackend::SQLite), TypeCategory::Binary);
+
+        // SQLite affinity for free-form declared types
+        assert!(type_named("UNSIGNED BIG INT").is_numeric(DatabaseBackend::SQLite));
+        assert!(type_named("NVARCHAR(40)").is_text(DatabaseBackend::SQLite));
+
+        let mut array = type_named("integer");
+        array.array_dimensions = Some(1);
+        assert_eq!(array.category(pg), TypeCategory::Other);
+    }
+
+    #[test]
+    fn test_array_column_type_display() {
+        let (element, array_dimensions) = ColumnType::split_array_type("integer[]");
+        let col_type = ColumnType {
+            base_type: element.to_string(),
+            length: None,
+            scale: None,
+            array_dimensions,
+        };
+        assert_eq!(col_type.base_type, "integer");
+        assert_eq!(col_type.to_string(), "integer[]");
+
+        let (element, array_dimensions) = ColumnType::split_array_type("text[][]");
+        let col_type = ColumnType {
+            base_type: element.to_string(),
+            length: None,
+            scale: None,
+            array_dimensions,
+        };
+        assert_eq!(col_type.to_string(), "text[][]");
+    }
+
     #[test]
     fn test_table_creation() {
         let table = Table::new("users");
@@ -443,6 +1469,7 @@ mod tests {
             references: None,
             is_unique: true,
             comment: None,
+            is_auto_increment: false,
         });
 
         index.add_table(table);
@@ -471,6 +1498,7 @@ mod tests {
             references: None,
             is_unique: true,
             comment: None,
+            is_auto_increment: false,
         });
 
         index.add_table(table);
@@ -480,4 +1508,595 @@ mod tests {
         assert!(formatted.contains("Table: users"));
         assert!(formatted.contains("id: integer PRIMARY KEY"));
     }
+
+    fn ddl_test_table() -> Table {
+        let int_type = ColumnType {
+            base_type: "integer".to_string(),
+            length: Some(32),
+            scale: None,
+            array_dimensions: None,
+        };
+        let mut table = Table::new("orders");
+        table.primary_keys.push("id".to_string());
+        table.add_column(Column {
+            name: "id".to_string(),
+            column_type: int_type.clone(),
+            nullable: false,
+            default_value: None,
+            is_primary_key: true,
+            is_foreign_key: false,
+            references: None,
+            is_unique: true,
+            comment: None,
+            is_auto_increment: false,
+        });
+        table.add_column(Column {
+            name: "user_id".to_string(),
+            column_type: int_type,
+            nullable: false,
+            default_value: None,
+            is_primary_key: false,
+            is_foreign_key: true,
+            references: Some(ForeignKeyReference {
+                table: "users".to_string(),
+                column: "id".to_string(),
+                on_delete: None,
+                on_update: None,
+            }),
+            is_unique: false,
+            comment: None,
+            is_auto_increment: false,
+        });
+        table.add_column(Column {
+            name: "note".to_string(),
+            column_type: ColumnType {
+                base_type: "character varying".to_string(),
+                length: Some(255),
+                scale: None,
+                array_dimensions: None,
+            },
+            nullable: true,
+            default_value: None,
+            is_primary_key: false,
+            is_foreign_key: false,
+            references: None,
+            is_unique: false,
+            comment: None,
+            is_auto_increment: false,
+        });
+        table
+    }
+
+    #[test]
+    fn test_schema_qualified_tables_do_not_collide() {
+        let mut index = SchemaIndex::new();
+        index.schema_name = Some("public".to_string());
+
+        let mut public_users = Table::new("users");
+        public_users.schema = Some("public".to_string());
+        public_users.comment = Some("app users".to_string());
+        let mut audit_users = Table::new("users");
+        audit_users.schema = Some("audit".to_string());
+        audit_users.comment = Some("audit trail".to_string());
+
+        index.add_table(public_users);
+        index.add_table(audit_users);
+
+        assert_eq!(index.tables.len(), 2);
+        assert_eq!(index.table_names(), vec!["audit.users", "public.users"]);
+        assert_eq!(
+            index.get_table("audit.users").unwrap().comment.as_deref(),
+            Some("audit trail")
+        );
+        assert_eq!(
+            index.get_table("users").unwrap().comment.as_deref(),
+            Some("app users")
+        );
+
+        index.schema_name = None;
+        assert!(index.get_table("users").is_none());
+        assert!(index.get_table("public.users").is_some());
+    }
+
+    #[test]
+    fn test_merge_combines_tables_and_relationships() {
+        let relationship = |from: &str, to: &str| TableRelationship {
+            from_table: from.to_string(),
+            from_column: format!("{}_id", to),
+            to_table: to.to_string(),
+            to_column: "id".to_string(),
+            relationship_type: "many-to-one".to_string(),
+        };
+
+        let mut public = SchemaIndex::new();
+        public.schema_name = Some("public".to_string());
+        let mut users = Table::new("users");
+        users.schema = Some("public".to_string());
+        let mut orders = Table::new("orders");
+        orders.schema = Some("public".to_string());
+        public.add_table(users);
+        public.add_table(orders);
+        public.relationships.push(relationship("public.orders", "public.users"));
+
+        let mut audit = SchemaIndex::new();
+        audit.schema_name = Some("audit".to_string());
+        audit.indexed_at = public.indexed_at - chrono::Duration::minutes(5);
+        let mut audit_users = Table::new("users");
+        audit_users.schema = Some("audit".to_string());
+        audit.add_table(audit_users);
+        audit.relationships.push(relationship("audit.users", "public.users"));
+        let earliest = audit.indexed_at;
+
+        public.merge(audit);
+
+        assert_eq!(public.tables.len(), 3);
+        assert_eq!(public.relationships.len(), 2);
+        assert_eq!(public.indexed_at, earliest);
+        assert_eq!(public.schema_name.as_deref(), Some("public"));
+        assert!(public.get_table("audit.users").is_some());
+        assert!(public.get_table("public.users").is_some());
+    }
+
+    #[test]
+    fn test_unqualified_tables_resolve_by_name() {
+        let mut index = SchemaIndex::new();
+        index.schema_name = Some("public".to_string());
+        index.add_table(Table::new("orders"));
+
+        assert!(index.get_table("orders").is_some());
+        assert!(index.get_table("public.orders").is_some());
+        assert!(index.get_table("audit.orders").is_none());
+    }
+
+    #[test]
+    fn test_table_summary_is_one_line_of_detailed_columns() {
+        let table = ddl_test_table();
+        let summary = table.format_summary();
+        let detailed = table.format_schema();
+
+        assert_eq!(summary.lines().count(), 1);
+        assert!(summary.starts_with(&format!("{} (", table.name)));
+        assert!(detailed.lines().count() > table.columns.len());
+        for column in &table.columns {
+            assert!(summary.contains(&format!("{}: {}", column.name, column.column_type.base_type)));
+            assert!(detailed.contains(&column.name));
+        }
+        assert!(summary.contains("[PK] id: "));
+    }
+
+    #[test]
+    fn test_type_coercion_note_lists_typed_columns() {
+        let column = |name: &str, base_type: &str| Column {
+            name: name.to_string(),
+            column_type: ColumnType {
+                base_type: base_type.to_string(),
+                length: None,
+                scale: None,
+                array_dimensions: None,
+            },
+            nullable: true,
+            default_value: None,
+            is_primary_key: false,
+            is_foreign_key: false,
+            references: None,
+            is_unique: false,
+            comment: None,
+            is_auto_increment: false,
+        };
+        let mut index = SchemaIndex::new();
+        let mut tags = Table::new("tags");
+        tags.add_column(column("name", "text"));
+        index.add_table(tags);
+        assert!(index.type_coercion_note(DatabaseBackend::PostgreSQL, &["tags"]).is_none());
+
+        let mut orders = Table::new("orders");
+        orders.add_column(column("id", "integer"));
+        orders.add_column(column("created_at", "timestamp"));
+        orders.add_column(column("paid", "boolean"));
+        index.add_table(orders);
+        let note = index.type_coercion_note(DatabaseBackend::PostgreSQL, &["orders", "tags"]).unwrap();
+        assert!(note.contains("  orders: created_at timestamp, paid boolean"), "{}", note);
+        assert!(!note.contains("tags") && !note.contains("id integer"));
+        assert!(index.type_coercion_note(DatabaseBackend::PostgreSQL, &["tags"]).is_none());
+    }
+
+    #[test]
+    fn test_column_order_is_display_only() {
+        let table = ddl_test_table();
+        let names = |order| {
+            table
+                .ordered_columns(order)
+                .iter()
+                .map(|column| column.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(ColumnOrder::Ordinal), ["id", "user_id", "note"]);
+        assert_eq!(names(ColumnOrder::Alpha), ["id", "note", "user_id"]);
+
+        let alpha = table.format_schema_ordered(ColumnOrder::Alpha);
+        assert!(alpha.find("    note:").unwrap() < alpha.find("    user_id:").unwrap());
+        let ordinal = table.format_schema();
+        assert!(ordinal.find("    user_id:").unwrap() < ordinal.find("    note:").unwrap());
+        let summary = table.format_summary_ordered(ColumnOrder::Alpha);
+        assert!(summary.find("note:").unwrap() < summary.find("user_id:").unwrap());
+        // The stored index keeps definition order
+        assert_eq!(table.columns[1].name, "user_id");
+    }
+
+    #[test]
+    fn test_partitions_are_noted_and_collapsed_in_summary() {
+        let mut index = SchemaIndex::new();
+        index.add_table(Table::new("events"));
+        for name in ["events_2024", "events_2025"] {
+            let mut partition = Table::new(name);
+            partition.is_partition = true;
+            partition.partition_of = Some("events".to_string());
+            index.add_table(partition);
+        }
+        let mut archive = Table::new("events_archive");
+        archive.partition_of = Some("events".to_string());
+        index.add_table(archive);
+
+        let partition = index.get_table("events_2024").unwrap();
+        assert_eq!(
+            partition.partition_note().as_deref(),
+            Some("events_2024 is a partition of events")
+        );
+        assert!(partition.format_schema().contains("-- events_2024 is a partition of events"));
+        assert_eq!(
+            index.get_table("events_archive").unwrap().partition_note().as_deref(),
+            Some("events_archive inherits from events")
+        );
+
+        let summary = index.format_summary_for_llm();
+        assert!(summary.contains("    partitions: events_2024, events_2025"), "{}", summary);
+        assert!(!summary.contains("  events_2024 ("));
+        // Inheriting tables are tables in their own right
+        assert!(summary.contains("  events_archive ("));
+    }
+
+    #[test]
+    fn test_json_object_keys_keep_only_names() {
+        let keys = json_object_keys(r#"{"id": 7, "tags": ["a"], "created": "2024-01-01"}"#).unwrap();
+        assert_eq!(keys.len(), 3);
+        for key in ["id", "tags", "created"] {
+            assert!(keys.contains(&key.to_string()));
+        }
+        assert!(!keys.iter().any(|key| key.contains("2024")));
+
+        assert!(json_object_keys("[1, 2]").is_none());
+        assert!(json_object_keys("not json").is_none());
+        assert!(is_json_type("JSONB") && !is_json_type("text"));
+
+        let mut table = Table::new("events");
+        table.add_column(Column {
+            name: "metadata".to_string(),
+            column_type: ColumnType {
+                base_type: "jsonb".to_string(),
+                length: None,
+                scale: None,
+                array_dimensions: None,
+            },
+            nullable: true,
+            default_value: None,
+            is_primary_key: false,
+            is_foreign_key: false,
+            references: None,
+            is_unique: false,
+            comment: None,
+            is_auto_increment: false,
+        });
+        table.json_keys.insert("metadata".to_string(), keys);
+        assert!(table.format_schema().contains("metadata: jsonb {keys: "));
+    }
+
+    #[test]
+    fn test_table_lookup_follows_backend_case_rules() {
+        let mut index = SchemaIndex::new();
+        index.add_table(Table::new("orders"));
+        index.add_table(Table::new("Customers"));
+
+        let sqlite = DatabaseBackend::SQLite;
+        assert!(index.get_table_for_backend("ORDERS", sqlite).is_some());
+        assert!(index.get_table_for_backend("customers", sqlite).is_some());
+
+        let mysql = DatabaseBackend::MySQL;
+        assert!(index.get_table_for_backend("orders", mysql).is_some());
+        assert!(index.get_table_for_backend("ORDERS", mysql).is_none());
+        assert!(index.get_table_for_backend("customers", mysql).is_none());
+
+        let postgres = DatabaseBackend::PostgreSQL;
+        assert!(index.get_table_for_backend("Orders", postgres).is_some());
+        assert!(index.get_table_for_backend("\"Customers\"", postgres).is_some());
+        assert!(index.get_table_for_backend("CUSTOMERS", postgres).is_none());
+    }
+
+    #[test]
+    fn test_unindexed_foreign_keys() {
+        let mut table = ddl_test_table();
+        let mut fk_column = table.get_column("user_id").unwrap().clone();
+        fk_column.name = "shipper_id".to_string();
+        table.add_column(fk_column);
+        table.indexes.push(TableIndex {
+            name: "idx_orders_user_id".to_string(),
+            columns: vec!["user_id".to_string()],
+            is_unique: false,
+        });
+
+        let mut index = SchemaIndex::new();
+        index.add_table(table);
+
+        assert_eq!(
+            index.unindexed_foreign_keys(),
+            vec![("orders".to_string(), "shipper_id".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_stats_reports_tables_without_primary_key() {
+        let mut index = SchemaIndex::new();
+        index.add_table(ddl_test_table());
+
+        let mut events = Table::new("events");
+        events.add_column(Column {
+            name: "payload".to_string(),
+            column_type: ColumnType {
+                base_type: "text".to_string(),
+                length: None,
+                scale: None,
+                array_dimensions: None,
+            },
+            nullable: true,
+            default_value: None,
+            is_primary_key: false,
+            is_foreign_key: false,
+            references: None,
+            is_unique: false,
+            comment: None,
+            is_auto_increment: false,
+        });
+        index.add_table(events);
+        index.add_table(Table::new_view("active_orders"));
+        index.relationships.push(TableRelationship {
+            from_table: "orders".to_string(),
+            from_column: "customer_id".to_string(),
+            to_table: "customers".to_string(),
+            to_column: "id".to_string(),
+            relationship_type: "many-to-one".to_string(),
+        });
+
+        let stats = index.stats();
+        assert_eq!(stats.table_count, 2);
+        assert_eq!(stats.view_count, 1);
+        assert_eq!(stats.column_count, ddl_test_table().columns.len() + 1);
+        assert_eq!(stats.relationship_count, 1);
+        assert_eq!(stats.tables_without_pk, vec!["events".to_string()]);
+        assert!(stats.to_string().contains("Tables without a primary key (1): events"));
+    }
+
+    #[test]
+    fn test_to_create_statement_postgresql() {
+        let ddl = ddl_test_table().to_create_statement(DatabaseBackend::PostgreSQL);
+        assert_eq!(
+            ddl,
+            "CREATE TABLE \"orders\" (\n    \"id\" integer NOT NULL,\n    \"user_id\" integer NOT NULL,\n    \"note\" character varying(255),\n    PRIMARY KEY (\"id\"),\n    FOREIGN KEY (\"user_id\") REFERENCES \"users\" (\"id\")\n);"
+        );
+    }
+
+    #[test]
+    fn test_to_create_statement_mysql() {
+        let ddl = ddl_test_table().to_create_statement(DatabaseBackend::MySQL);
+        assert_eq!(
+            ddl,
+            "CREATE TABLE `orders` (\n    `id` integer NOT NULL,\n    `user_id` integer NOT NULL,\n    `note` VARCHAR(255),\n    PRIMARY KEY (`id`),\n    FOREIGN KEY (`user_id`) REFERENCES `users` (`id`)\n);"
+        );
+    }
+
+    #[test]
+    fn test_retain_relevant_tables_enforces_cap() {
+        let mut index = SchemaIndex::new();
+        for name in ["accounts", "invoices", "orders", "users"] {
+            index.add_table(Table::new(name));
+        }
+        index.relationships.push(TableRelationship {
+            from_table: "orders".to_string(),
+            from_column: "user_id".to_string(),
+            to_table: "users".to_string(),
+            to_column: "id".to_string(),
+            relationship_type: "many-to-one".to_string(),
+        });
+
+        let (limited, omitted) = index.retain_relevant_tables("how many orders per user?", 2);
+        assert_eq!(omitted, 2);
+        assert_eq!(limited.table_names(), vec!["orders", "users"]);
+        assert_eq!(limited.relationships.len(), 1);
+
+        let (limited, omitted) = index.retain_relevant_tables("anything", 1);
+        assert_eq!(omitted, 3);
+        assert_eq!(limited.tables.len(), 1);
+
+        let (unchanged, omitted) = index.retain_relevant_tables("orders", 10);
+        assert_eq!(omitted, 0);
+        assert_eq!(unchanged.tables.len(), 4);
+    }
+
+    #[test]
+    fn test_llm_formatting_within_budget_keeps_whole_tables() {
+        let mut index = SchemaIndex::new();
+        for name in ["accounts", "orders", "users"] {
+            let mut table = Table::new(name);
+            table.add_column(Column {
+                name: "id".to_string(),
+                column_type: ColumnType {
+                    base_type: "integer".to_string(),
+                    length: None,
+                    scale: None,
+                    array_dimensions: None,
+                },
+                nullable: false,
+                default_value: None,
+                is_primary_key: true,
+                is_foreign_key: false,
+                references: None,
+                is_unique: true,
+                comment: None,
+                is_auto_increment: false,
+            });
+            index.add_table(table);
+        }
+
+        let full = index.format_for_llm();
+        assert_eq!(index.format_for_llm_within(full.len()), full);
+
+        let (budgeted, included) = index.format_tables_within(120);
+        assert_eq!(budgeted, index.format_for_llm_within(120));
+        assert_eq!(included, ["accounts"]);
+        assert!(budgeted.len() <= 200);
+        assert!(budgeted.contains("Table: accounts"));
+        assert!(!budgeted.contains("Table: users"));
+        assert!(budgeted.contains("tables omitted"));
+    }
+
+    #[test]
+    fn test_relationships_context_omits_columns() {
+        let mut index = SchemaIndex::new();
+        for name in ["customers", "orders"] {
+            let mut table = Table::new(name);
+            table.add_column(Column {
+                name: "customer_id".to_string(),
+                column_type: ColumnType {
+                    base_type: "integer".to_string(),
+                    length: None,
+                    scale: None,
+                    array_dimensions: None,
+                },
+                nullable: false,
+                default_value: None,
+                is_primary_key: name == "customers",
+                is_foreign_key: name == "orders",
+                references: None,
+                is_unique: false,
+                comment: None,
+                is_auto_increment: false,
+            });
+            index.add_table(table);
+        }
+
+        let context = index.format_relationships_for_llm();
+        assert!(context.contains("  customers\n"));
+        assert!(context.contains("No foreign key relationships are indexed."));
+
+        index.relationships.push(TableRelationship {
+            from_table: "orders".to_string(),
+            from_column: "customer_id".to_string(),
+            to_table: "customers".to_string(),
+            to_column: "customer_id".to_string(),
+            relationship_type: "many-to-one".to_string(),
+        });
+        let context = index.format_relationships_for_llm();
+        assert!(context.contains("Relationships:\n  orders.customer_id -> customers.customer_id (many-to-one)"));
+        assert!(context.contains("  orders\n"));
+        assert!(!context.contains("integer"));
+        assert!(!context.contains("PRIMARY KEY"));
+        assert!(index.format_for_llm().contains("integer"));
+    }
+
+    #[test]
+    fn test_drop_unusable_tables_skips_empty_and_system_tables() {
+        let build = || {
+            let mut index = SchemaIndex::new();
+            for name in ["orders", "pg_stat_statements"] {
+                let mut table = Table::new(name);
+                table.add_column(Column {
+                    name: "id".to_string(),
+                    column_type: ColumnType {
+                        base_type: "integer".to_string(),
+                        length: None,
+                        scale: None,
+                        array_dimensions: None,
+                    },
+                    nullable: false,
+                    default_value: None,
+                    is_primary_key: false,
+                    is_foreign_key: false,
+                    references: None,
+                    is_unique: false,
+                    comment: None,
+                    is_auto_increment: false,
+                });
+                index.add_table(table);
+            }
+            // A foreign table whose columns could not be read
+            index.add_table(Table::new("remote_events"));
+            index.relationships.push(TableRelationship {
+                from_table: "remote_events".to_string(),
+                from_column: "order_id".to_string(),
+                to_table: "orders".to_string(),
+                to_column: "id".to_string(),
+                relationship_type: "many-to-one".to_string(),
+            });
+            index
+        };
+
+        let mut index = build();
+        index.drop_unusable_tables(false);
+        assert_eq!(index.table_names(), vec!["orders"]);
+        assert!(index.relationships.is_empty());
+        assert_eq!(
+            index.skipped_tables,
+            vec!["pg_stat_statements (system table)", "remote_events (no columns)"]
+        );
+
+        let mut index = build();
+        index.drop_unusable_tables(true);
+        assert_eq!(index.table_names(), vec!["orders", "pg_stat_statements"]);
+        assert_eq!(index.skipped_tables, vec!["remote_events (no columns)"]);
+    }
+
+    #[test]
+    fn test_snapshot_round_trips() {
+        let mut index = SchemaIndex::new();
+        index.database_name = Some("shop".to_string());
+        let mut table = Table::new("orders");
+        table.add_column(Column {
+            name: "id".to_string(),
+            column_type: ColumnType {
+                base_type: "numeric".to_string(),
+                length: Some(10),
+                scale: Some(2),
+                array_dimensions: None,
+            },
+            nullable: false,
+            default_value: Some("0".to_string()),
+            is_primary_key: true,
+            is_foreign_key: false,
+            references: None,
+            is_unique: true,
+            comment: Some("order id".to_string()),
+            is_auto_increment: false,
+        });
+        index.add_table(table);
+        index.relationships.push(TableRelationship {
+            from_table: "orders".to_string(),
+            from_column: "user_id".to_string(),
+            to_table: "users".to_string(),
+            to_column: "id".to_string(),
+            relationship_type: "many-to-one".to_string(),
+        });
+
+        let dir = std::env::temp_dir().join(format!("schema-forge-snapshots-{}", std::process::id()));
+        let path = index.save_snapshot(&dir).unwrap();
+        let loaded = SchemaIndex::load_snapshot(&path);
+        // Saved again straight away, usually within the same second
+        let again = index.save_snapshot(&dir).unwrap();
+        let first_kept = SchemaIndex::load_snapshot(&path);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        assert!(name.starts_with("schema-") && name.ends_with(".json"));
+        assert_eq!(name.len(), "schema-YYYYMMDD-HHMMSS.json".len());
+        assert_eq!(loaded.unwrap(), index);
+        assert_ne!(again, path);
+        assert_eq!(first_kept.unwrap(), index);
+    }
 }
@@ -0,0 +1,1037 @@
+//! SQL Text Utilities
+//!
+//! Helpers that work on SQL source text without talking to a database.
+
+/// Split a SQL script into individual statements
+///
+/// Semicolons inside quoted strings, quoted identifiers, comments and
+/// PostgreSQL dollar-quoted bodies do not end a statement. Empty statements
+/// and comment-only fragments are dropped; returned statements are trimmed
+/// and have no trailing semicolon.
+pub fn split_statements(script: &str) -> Vec<String> {
+    let chars: Vec<char> = script.chars().collect();
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut has_code = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        match c {
+            '\'' | '"' | '`' => {
+                // Copy through the closing quote; doubled quotes are escapes
+                current.push(c);
+                i += 1;
+                while i < chars.len() {
+                    current.push(chars[i]);
+                    if chars[i] == c {
+                        if chars.get(i + 1) == Some(&c) {
+                            current.push(c);
+                            i += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    i += 1;
+                }
+                has_code = true;
+            }
+            '-' if next == Some('-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    current.push(chars[i]);
+                    i += 1;
+                }
+                continue;
+            }
+            '/' if next == Some('*') => {
+                current.push_str("/*");
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    current.push(chars[i]);
+                    i += 1;
+                }
+                if i < chars.len() {
+                    current.push_str("*/");
+                    i += 1;
+                }
+            }
+            '$' => match dollar_quote_tag(&chars[i..]) {
+                Some(tag) => {
+                    let tag_chars: Vec<char> = tag.chars().collect();
+                    current.push_str(&tag);
+                    i += tag_chars.len();
+                    while i < chars.len() && !chars[i..].starts_with(&tag_chars) {
+                        current.push(chars[i]);
+                        i += 1;
+                    }
+                    if i < chars.len() {
+                        current.push_str(&tag);
+                        i += tag_chars.len() - 1;
+                    }
+                    has_code = true;
+                }
+                None => {
+                    current.push(c);
+                    has_code = true;
+                }
+            },
+            ';' => {
+                if has_code {
+                    statements.push(current.trim().to_string());
+                }
+                current.clear();
+                has_code = false;
+            }
+            _ => {
+                current.push(c);
+                if !c.is_whitespace() {
+                    has_code = true;
+                }
+            }
+        }
+        i += 1;
+    }
+
+    if has_code {
+        statements.push(current.trim().to_string());
+    }
+
+    statements
+}
+
+/// What a statement does to the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatementKind {
+    /// Query that does not change data (SELECT, SHOW, EXPLAIN, ...)
+    Read,
+    /// Plain INSERT
+    Insert,
+    /// INSERT that may update existing rows (ON CONFLICT, ON DUPLICATE KEY
+    /// UPDATE, INSERT OR REPLACE, REPLACE INTO)
+    Upsert,
+    /// MERGE statement
+    Merge,
+    /// UPDATE statement
+    Update,
+    /// DELETE statement
+    Delete,
+    /// TRUNCATE statement
+    Truncate,
+    /// DROP statement
+    Drop,
+    /// Other schema changes (CREATE, ALTER, RENAME, ...)
+    Ddl,
+    /// Anything not recognised; treated as mutating
+    Other,
+}
+
+impl StatementKind {
+    /// Whether the statement can change data or schema
+    pub fn is_mutating(self) -> bool {
+        !matches!(self, StatementKind::Read)
+    }
+
+    /// Whether the statement changes the schema (CREATE, ALTER, DROP, ...)
+    pub fn changes_schema(self) -> bool {
+        matches!(self, StatementKind::Drop | StatementKind::Ddl)
+    }
+}
+
+/// Classify a single SQL statement by its leading keywords
+///
+/// `EXPLAIN ANALYZE` executes its statement, so it is classified as the
+/// statement it wraps; a plain `EXPLAIN` is always a read. A `WITH` prefix
+/// is skipped to reach the main statement, and a CTE that itself changes
+/// data (PostgreSQL's `WITH d AS (DELETE ... RETURNING *)`) makes the
+/// whole statement mutating.
+pub fn classify_statement(sql: &str) -> StatementKind {
+    classify_words(&code_words(sql))
+}
+
+/// [`classify_statement`] over words from [`code_words`]
+fn classify_words(words: &[String]) -> StatementKind {
+    // A parenthesized statement, as in `(SELECT 1) UNION (SELECT 2)`, is
+    // classified by its first keyword
+    let words = &words[words.iter().take_while(|w| *w == "(").count()..];
+    let has_sequence = |sequence: &[&str]| words.windows(sequence.len()).any(|w| w == sequence);
+
+    match words.first().map(String::as_str) {
+        Some("EXPLAIN") if words.iter().any(|w| w == "ANALYZE" || w == "ANALYSE") => words
+            .iter()
+            .position(|w| {
+                matches!(
+                    w.as_str(),
+                    "SELECT" | "WITH" | "VALUES" | "TABLE" | "INSERT" | "REPLACE" | "MERGE"
+                        | "UPDATE" | "DELETE" | "CREATE"
+                )
+            })
+            .map_or(StatementKind::Read, |start| classify_words(&words[start..])),
+        Some("WITH") => {
+            let (definitions, main) = split_ctes(words);
+            definitions
+                .into_iter()
+                .map(classify_words)
+                .find(|kind| kind.is_mutating())
+                .unwrap_or_else(|| classify_words(main))
+        }
+        Some("SELECT" | "SHOW" | "EXPLAIN" | "DESCRIBE" | "DESC" | "VALUES" | "TABLE") => {
+            StatementKind::Read
+        }
+        Some("INSERT") => {
+            if has_sequence(&["ON", "CONFLICT"])
+                || has_sequence(&["ON", "DUPLICATE", "KEY", "UPDATE"])
+                || has_sequence(&["INSERT", "OR", "REPLACE"])
+            {
+                StatementKind::Upsert
+            } else {
+                StatementKind::Insert
+            }
+        }
+        Some("REPLACE") => StatementKind::Upsert,
+        Some("MERGE") => StatementKind::Merge,
+        Some("UPDATE") => StatementKind::Update,
+        Some("DELETE") => StatementKind::Delete,
+        Some("TRUNCATE") => StatementKind::Truncate,
+        Some("DROP") => StatementKind::Drop,
+        Some("CREATE" | "ALTER" | "RENAME" | "COMMENT") => StatementKind::Ddl,
+        _ => StatementKind::Other,
+    }
+}
+
+/// Check whether a statement destroys data without a row filter
+///
+/// DROP and TRUNCATE always qualify; UPDATE and DELETE qualify when they
+/// have no WHERE clause. Inserts, upserts and MERGE never do, since they
+/// only touch rows the statement explicitly matches or supplies.
+pub fn is_unguarded_destructive(sql: &str) -> bool {
+    let words = code_words(sql);
+    match classify_words(&words) {
+        StatementKind::Drop | StatementKind::Truncate => true,
+        // A WHERE inside a CTE does not filter the main statement
+        StatementKind::Update | StatementKind::Delete => {
+            !split_ctes(&words).1.iter().any(|word| word == "WHERE")
+        }
+        _ => false,
+    }
+}
+
+/// Split a `WITH` statement into its CTE bodies and its main statement
+///
+/// Statements without `WITH` come back whole. When the CTE list cannot be
+/// parsed the main statement is empty, which classifies as
+/// [`StatementKind::Other`].
+fn split_ctes(words: &[String]) -> (Vec<&[String]>, &[String]) {
+    if words.first().map(String::as_str) != Some("WITH") {
+        return (Vec::new(), words);
+    }
+    let is = |i: usize, word: &str| words.get(i).is_some_and(|w| w == word);
+    let mut definitions = Vec::new();
+    let mut i = if is(1, "RECURSIVE") { 2 } else { 1 };
+
+    loop {
+        // name [(columns)] AS [NOT] [MATERIALIZED] (body); a quoted name
+        // leaves no word behind
+        if !is(i, "AS") && !is(i, "(") {
+            i += 1;
+        }
+        if is(i, "(") {
+            match group_end(words, i) {
+                Some(end) => i = end + 1,
+                None => return (definitions, &[]),
+            }
+        }
+        if !is(i, "AS") {
+            return (definitions, &[]);
+        }
+        i += 1;
+        if is(i, "NOT") {
+            i += 1;
+        }
+        if is(i, "MATERIALIZED") {
+            i += 1;
+        }
+        let Some(end) = is(i, "(").then(|| group_end(words, i)).flatten() else {
+            return (definitions, &[]);
+        };
+        definitions.push(&words[i + 1..end]);
+        i = end + 1;
+        if !is(i, ",") {
+            return (definitions, &words[i.min(words.len())..]);
+        }
+        i += 1;
+    }
+}
+
+/// Index of the `)` closing the `(` at `open`
+fn group_end(words: &[String], open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, word) in words.iter().enumerate().skip(open) {
+        match word.as_str() {
+            "(" => depth += 1,
+            ")" => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// A statement without surrounding whitespace and one trailing semicolon
+///
+/// Only the final semicolon goes, so `SELECT 1; SELECT 2;` still splits
+/// into two statements. PL/SQL blocks keep theirs, since Oracle requires
+/// the semicolon after the closing `END`.
+pub fn trim_trailing_semicolon(sql: &str) -> &str {
+    let trimmed = sql.trim();
+    if is_plsql_block(trimmed) {
+        return trimmed;
+    }
+    trimmed.strip_suffix(';').map_or(trimmed, str::trim_end)
+}
+
+/// Whether `sql` is an anonymous block or stored program ending in
+/// `END [name];`
+///
+/// A bare `BEGIN;` that opens a transaction is not a block.
+fn is_plsql_block(sql: &str) -> bool {
+    if !sql.ends_with(';') {
+        return false;
+    }
+    let words = code_words(sql);
+    let starts_block = match words.first().map(String::as_str) {
+        Some("BEGIN" | "DECLARE") => true,
+        Some("CREATE") => words.iter().take(6).any(|word| {
+            matches!(word.as_str(), "PROCEDURE" | "FUNCTION" | "PACKAGE" | "TRIGGER" | "TYPE")
+        }),
+        _ => false,
+    };
+    let tail: Vec<&str> = words.iter().rev().take(2).map(String::as_str).collect();
+    starts_block && matches!(tail.as_slice(), ["END", ..] | [_, "END"])
+}
+
+/// Clauses that start on a new line in [`format_sql`]
+const CLAUSE_KEYWORDS: &[&str] = &[
+    "FROM", "WHERE", "JOIN", "LEFT", "RIGHT", "INNER", "FULL", "CROSS", "GROUP", "ORDER",
+    "HAVING", "LIMIT", "OFFSET", "FETCH", "UNION", "EXCEPT", "INTERSECT",
+];
+
+/// Lay a statement out over several lines for display
+///
+/// Only whitespace before a major clause (FROM, WHERE, JOIN, GROUP BY,
+/// ORDER BY, LIMIT, ...) is replaced, by a newline indented two spaces per
+/// subquery level. Strings, quoted identifiers, comments and dollar-quoted
+/// bodies are copied untouched, and clause words inside function calls such as
+/// `EXTRACT(YEAR FROM d)` or `OVER (ORDER BY x)` stay inline.
+pub fn format_sql(sql: &str) -> String {
+    let chars: Vec<char> = sql.trim().chars().collect();
+    let mut output = String::new();
+    // One entry per open parenthesis: whether it holds a subquery
+    let mut parens: Vec<bool> = Vec::new();
+    let mut after_open_paren = false;
+    let mut previous_word = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        // Display only, so MySQL `#` comments and `[bracketed]` names are
+        // copied through too
+        let verbatim_end = skip_verbatim(&chars, i, true);
+        if let Some(end) = verbatim_end {
+            output.extend(&chars[i..end]);
+            after_open_paren = false;
+            i = end;
+            continue;
+        }
+
+        if c.is_alphanumeric() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            let upper = word.to_uppercase();
+
+            if after_open_paren && (upper == "SELECT" || upper == "WITH") {
+                if let Some(top) = parens.last_mut() {
+                    *top = true;
+                }
+            }
+            after_open_paren = false;
+
+            let in_query_scope = parens.last().copied().unwrap_or(true);
+            let joins_previous = upper == "JOIN"
+                && matches!(
+                    previous_word.as_str(),
+                    "LEFT" | "RIGHT" | "INNER" | "FULL" | "CROSS" | "OUTER" | "NATURAL"
+                );
+            // LEFT(...) and RIGHT(...) are string functions, not joins
+            let is_function_call = matches!(upper.as_str(), "LEFT" | "RIGHT")
+                && chars[i..]
+                    .iter()
+                    .find(|ch| !ch.is_whitespace())
+                    .is_some_and(|ch| *ch == '(');
+            let breaks = in_query_scope
+                && !joins_previous
+                && !is_function_call
+                && CLAUSE_KEYWORDS.contains(&upper.as_str())
+                && output.ends_with(|ch: char| ch.is_whitespace());
+            if breaks {
+                let depth = parens.iter().filter(|is_subquery| **is_subquery).count();
+                output.truncate(output.trim_end().len());
+                output.push('\n');
+                output.push_str(&"  ".repeat(depth));
+            }
+
+            output.push_str(&word);
+            previous_word = upper;
+            continue;
+        }
+
+        match c {
+            '(' => {
+                parens.push(false);
+                after_open_paren = true;
+            }
+            ')' => {
+                parens.pop();
+                after_open_paren = false;
+            }
+            _ if c.is_whitespace() => {}
+            _ => after_open_paren = false,
+        }
+        output.push(c);
+        i += 1;
+    }
+
+    output
+}
+
+/// Table read by an unfiltered, unbounded `SELECT * FROM <table>`
+///
+/// Returns `None` when the statement has a row limit (LIMIT, FETCH, TOP,
+/// ROWNUM), a WHERE filter, or reads from more than one source.
+pub fn unbounded_select_star_table(sql: &str) -> Option<String> {
+    let bounded = code_words(sql).iter().any(|word| {
+        matches!(
+            word.as_str(),
+            "LIMIT" | "FETCH" | "TOP" | "ROWNUM" | "WHERE" | "JOIN" | "UNION" | "GROUP"
+        )
+    });
+    if bounded {
+        return None;
+    }
+
+    let mut tokens = trim_trailing_semicolon(sql).split_whitespace();
+    match (tokens.next(), tokens.next(), tokens.next(), tokens.next()) {
+        (Some(select), Some("*"), Some(from), Some(table))
+            if select.eq_ignore_ascii_case("SELECT")
+                && from.eq_ignore_ascii_case("FROM")
+                && !table.contains(',') =>
+        {
+            Some(table.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Tables and qualified columns a statement refers to
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SqlReferences {
+    /// Tables named after FROM or JOIN, schema-qualified as written
+    pub tables: Vec<String>,
+    /// `(table, column)` pairs from `table.column` or `alias.column`
+    pub columns: Vec<(String, String)>,
+}
+
+/// Words that end a FROM/JOIN source list or cannot be an alias
+const NON_ALIAS_WORDS: &[&str] = &[
+    "ALL", "AND", "ANY", "AS", "BY", "CROSS", "ELSE", "END", "EXCEPT", "EXISTS", "FETCH",
+    "FOR", "FROM", "FULL", "GROUP", "HAVING", "IN", "INNER", "INTERSECT", "IS", "JOIN",
+    "LATERAL", "LEFT", "LIMIT", "MINUS", "NATURAL", "NOT", "OFFSET", "ON", "ONLY", "OR",
+    "ORDER", "OUTER", "QUALIFY", "RETURNING", "RIGHT", "SELECT", "SET", "UNION", "USING",
+    "VALUES", "WHERE", "WINDOW", "WITH",
+];
+
+/// Collect the tables and qualified columns `sql` refers to
+///
+/// A minimal extractor, not a parser: tables come from FROM and JOIN
+/// clauses (aliases are resolved), and columns only from qualified
+/// references whose qualifier names one of those tables. CTE names,
+/// subqueries and table functions are skipped, as are FROM keywords inside
+/// function calls such as `EXTRACT(YEAR FROM d)`.
+pub fn referenced_identifiers(sql: &str) -> SqlReferences {
+    let tokens = sql_tokens(sql);
+    let is_word = |index: usize, word: &str| {
+        matches!(tokens.get(index), Some(SqlToken::Name(parts))
+            if parts.len() == 1 && parts[0].eq_ignore_ascii_case(word))
+    };
+    let is_alias = |index: usize| {
+        matches!(tokens.get(index), Some(SqlToken::Name(parts))
+            if parts.len() == 1 && !NON_ALIAS_WORDS.contains(&parts[0].to_uppercase().as_str()))
+    };
+
+    // `name AS (` introduces a common table expression
+    let cte_names: Vec<String> = (0..tokens.len())
+        .filter(|&i| is_word(i + 1, "AS") && tokens.get(i + 2) == Some(&SqlToken::Symbol('(')))
+        .filter_map(|i| match &tokens[i] {
+            SqlToken::Name(parts) if parts.len() == 1 => Some(parts[0].to_lowercase()),
+            _ => None,
+        })
+        .collect();
+
+    let mut references = SqlReferences::default();
+    // Lower-cased qualifier (alias or table name) and the table it names
+    let mut qualifiers: Vec<(String, String)> = Vec::new();
+    let mut source_positions = Vec::new();
+    // One entry per open parenthesis: whether it holds a subquery
+    let mut parens: Vec<bool> = Vec::new();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        match &tokens[i] {
+            SqlToken::Symbol('(') => {
+                parens.push(is_word(i + 1, "SELECT") || is_word(i + 1, "WITH"));
+                i += 1;
+                continue;
+            }
+            SqlToken::Symbol(')') => {
+                parens.pop();
+                i += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        let in_query_scope = parens.last().copied().unwrap_or(true);
+        let starts_sources = is_word(i, "FROM") || is_word(i, "JOIN");
+        i += 1;
+        if !in_query_scope || !starts_sources {
+            continue;
+        }
+
+        while let Some(SqlToken::Name(parts)) = tokens.get(i) {
+            // Keywords such as LATERAL end the list; table functions such as
+            // generate_series(...) are not tables
+            if (parts.len() == 1 && !is_alias(i))
+                || tokens.get(i + 1) == Some(&SqlToken::Symbol('('))
+            {
+                break;
+            }
+            let table = parts.join(".");
+            let position = i;
+            i += 1;
+            if is_word(i, "AS") {
+                i += 1;
+            }
+            let alias = match tokens.get(i) {
+                Some(SqlToken::Name(alias)) if is_alias(i) => {
+                    i += 1;
+                    Some(alias[0].to_lowercase())
+                }
+                _ => None,
+            };
+
+            if parts.len() > 1 || !cte_names.contains(&parts[0].to_lowercase()) {
+                source_positions.push(position);
+                qualifiers.push((parts[parts.len() - 1].to_lowercase(), table.clone()));
+                if let Some(alias) = alias {
+                    qualifiers.push((alias, table.clone()));
+                }
+                if !references.tables.contains(&table) {
+                    references.tables.push(table);
+                }
+            }
+
+            if tokens.get(i) != Some(&SqlToken::Symbol(',')) {
+                break;
+            }
+            i += 1;
+        }
+    }
+
+    for (position, token) in tokens.iter().enumerate() {
+        let SqlToken::Name(parts) = token else {
+            continue;
+        };
+        if parts.len() != 2
+            || source_positions.contains(&position)
+            || tokens.get(position + 1) == Some(&SqlToken::Symbol('('))
+        {
+            continue;
+        }
+        let qualifier = parts[0].to_lowercase();
+        // An alias shadows a table of the same name
+        if let Some((_, table)) = qualifiers.iter().rev().find(|(name, _)| *name == qualifier) {
+            let reference = (table.clone(), parts[1].clone());
+            if !references.columns.contains(&reference) {
+                references.columns.push(reference);
+            }
+        }
+    }
+
+    references
+}
+
+/// A token of SQL source for [`referenced_identifiers`]
+#[derive(Debug, Clone, PartialEq)]
+enum SqlToken {
+    /// Word or identifier with its dotted parts, quotes removed
+    Name(Vec<String>),
+    /// Punctuation; a string literal becomes a single `'`
+    Symbol(char),
+}
+
+/// Split SQL into names and symbols, dropping whitespace and comments
+fn sql_tokens(sql: &str) -> Vec<SqlToken> {
+    let chars: Vec<char> = sql.chars().collect();
+    let starts_name = |c: char| c.is_alphanumeric() || matches!(c, '_' | '"' | '`' | '[');
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c == '-' && next == Some('-') {
+            i = chars[i..]
+                .iter()
+                .position(|ch| *ch == '\n')
+                .map_or(chars.len(), |offset| i + offset + 1);
+        } else if c == '/' && next == Some('*') {
+            i = (i + 2..chars.len())
+                .find(|&j| chars[j] == '*' && chars.get(j + 1) == Some(&'/'))
+                .map_or(chars.len(), |j| j + 2);
+        } else if c == '\'' {
+            i = quoted_end(&chars, i, '\'');
+            tokens.push(SqlToken::Symbol('\''));
+        } else if starts_name(c) {
+            let mut parts = Vec::new();
+            loop {
+                let (part, end) = match chars[i] {
+                    quote @ ('"' | '`' | '[') => {
+                        let close = if quote == '[' { ']' } else { quote };
+                        let end = quoted_end(&chars, i, close);
+                        let inner: String = chars[i + 1..end.saturating_sub(1).max(i + 1)]
+                            .iter()
+                            .collect();
+                        (inner.replace(&format!("{}{}", close, close), &close.to_string()), end)
+                    }
+                    _ => {
+                        let end = (i..chars.len())
+                            .find(|&j| !(chars[j].is_alphanumeric() || matches!(chars[j], '_' | '$')))
+                            .unwrap_or(chars.len());
+                        (chars[i..end].iter().collect(), end)
+                    }
+                };
+                parts.push(part);
+                i = end;
+                if chars.get(i) == Some(&'.') && chars.get(i + 1).is_some_and(|ch| starts_name(*ch)) {
+                    i += 1;
+                } else {
+                    break;
+                }
+            }
+            tokens.push(SqlToken::Name(parts));
+        } else {
+            if !c.is_whitespace() {
+                tokens.push(SqlToken::Symbol(c));
+            }
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+/// Index just past the quote opened at `start` and closed by `close`
+///
+/// A doubled closing quote is an escape, except for `]`.
+fn quoted_end(chars: &[char], start: usize, close: char) -> usize {
+    let mut j = start + 1;
+    while j < chars.len() {
+        if chars[j] == close {
+            if close != ']' && chars.get(j + 1) == Some(&close) {
+                j += 2;
+                continue;
+            }
+            return j + 1;
+        }
+        j += 1;
+    }
+    chars.len()
+}
+
+/// Upper-cased words, parentheses and commas outside strings, quoted
+/// identifiers, comments and dollar-quoted bodies
+fn code_words(sql: &str) -> Vec<String> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let skip_to = skip_verbatim(&chars, i, false);
+
+        if c.is_alphanumeric() || c == '_' {
+            current.extend(c.to_uppercase());
+            i += 1;
+            continue;
+        }
+        if !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        if matches!(c, '(' | ')' | ',') {
+            words.push(c.to_string());
+        }
+        i = skip_to.unwrap_or(i + 1);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
+/// End of the string, quoted identifier, comment or `$tag$`-quoted body
+/// starting at `chars[i]`, or `None` when ordinary code starts there
+///
+/// A `--` comment ends before its newline. With `display`, MySQL `#`
+/// comments and `[bracketed]` identifiers count as well; elsewhere `#` is
+/// an operator and `[` an array subscript, so code that classifies
+/// statements leaves them out.
+fn skip_verbatim(chars: &[char], i: usize, display: bool) -> Option<usize> {
+    let c = chars[i];
+    let next = chars.get(i + 1).copied();
+    match c {
+        '\'' | '"' | '`' | '[' if c != '[' || display => {
+            let close = if c == '[' { ']' } else { c };
+            let mut j = i + 1;
+            while j < chars.len() {
+                if chars[j] == close {
+                    if close != ']' && chars.get(j + 1) == Some(&close) {
+                        j += 2;
+                        continue;
+                    }
+                    break;
+                }
+                j += 1;
+            }
+            Some((j + 1).min(chars.len()))
+        }
+        '-' if next == Some('-') => Some(line_end(chars, i)),
+        '#' if display => Some(line_end(chars, i)),
+        '/' if next == Some('*') => Some(
+            (i + 2..chars.len())
+                .find(|&j| chars[j] == '*' && chars.get(j + 1) == Some(&'/'))
+                .map_or(chars.len(), |j| j + 2),
+        ),
+        // `a$b` is an identifier, not the start of a quoted body
+        '$' if !(i > 0 && (chars[i - 1].is_alphanumeric() || matches!(chars[i - 1], '_' | '$'))) => {
+            let tag: Vec<char> = dollar_quote_tag(&chars[i..])?.chars().collect();
+            let body = i + tag.len();
+            Some(
+                (body..chars.len())
+                    .find(|&j| chars[j..].starts_with(&tag))
+                    .map_or(chars.len(), |j| j + tag.len()),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// Index of the newline ending the line that `chars[i]` is on
+fn line_end(chars: &[char], i: usize) -> usize {
+    chars[i..]
+        .iter()
+        .position(|ch| *ch == '\n')
+        .map_or(chars.len(), |offset| i + offset)
+}
+
+/// Read a `$tag$` opener at the start of `chars`, if present
+fn dollar_quote_tag(chars: &[char]) -> Option<String> {
+    let end = chars.iter().skip(1).position(|c| *c == '$')? + 1;
+    let tag = &chars[1..end];
+    if tag.iter().all(|c| c.is_alphanumeric() || *c == '_')
+        && !tag.first().is_some_and(|c| c.is_ascii_digit())
+    {
+        Some(chars[..=end].iter().collect())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_sql_breaks_before_clauses() {
+        assert_eq!(
+            format_sql("SELECT id, name FROM users WHERE active = 1 ORDER BY name LIMIT 10"),
+            "SELECT id, name\nFROM users\nWHERE active = 1\nORDER BY name\nLIMIT 10"
+        );
+        assert_eq!(
+            format_sql(
+                "select u.name, count(*) from users u left join orders o on o.user_id = u.id group by u.name"
+            ),
+            "select u.name, count(*)\nfrom users u\nleft join orders o on o.user_id = u.id\ngroup by u.name"
+        );
+    }
+
+    #[test]
+    fn test_format_sql_indents_subqueries_only() {
+        assert_eq!(
+            format_sql("SELECT * FROM (SELECT id FROM t WHERE x > 1) s WHERE EXTRACT(YEAR FROM d) = 2024"),
+            "SELECT *\nFROM (SELECT id\n  FROM t\n  WHERE x > 1) s\nWHERE EXTRACT(YEAR FROM d) = 2024"
+        );
+        assert_eq!(
+            format_sql("SELECT ROW_NUMBER() OVER (ORDER BY id) FROM t"),
+            "SELECT ROW_NUMBER() OVER (ORDER BY id)\nFROM t"
+        );
+    }
+
+    #[test]
+    fn test_format_sql_leaves_literals_alone() {
+        let sql = "SELECT 'a FROM b' AS \"where\" FROM t -- order by\n";
+        assert_eq!(
+            format_sql(sql),
+            "SELECT 'a FROM b' AS \"where\"\nFROM t -- order by"
+        );
+        assert_eq!(
+            format_sql("SELECT a FROM t # where b\nWHERE c = 1"),
+            "SELECT a\nFROM t # where b\nWHERE c = 1"
+        );
+        let body = "CREATE FUNCTION f() RETURNS int AS $body$ SELECT 1 FROM t WHERE x $body$ LANGUAGE sql";
+        assert_eq!(
+            format_sql(body),
+            "CREATE FUNCTION f() RETURNS int AS $body$ SELECT 1 FROM t WHERE x $body$ LANGUAGE sql"
+        );
+        assert_eq!(format_sql("SELECT $$a FROM b$$ FROM t"), "SELECT $$a FROM b$$\nFROM t");
+        assert_eq!(format_sql("SELECT a$b FROM t"), "SELECT a$b\nFROM t");
+
+        let words = |text: &str| text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let long = "SELECT a FROM t JOIN u ON u.id = t.id WHERE a IN (SELECT b FROM v) UNION SELECT c FROM w";
+        assert_eq!(words(&format_sql(long)), words(long));
+    }
+
+    #[test]
+    fn test_unbounded_select_star_table() {
+        assert_eq!(
+            unbounded_select_star_table("select * from events;"),
+            Some("events".to_string())
+        );
+        assert_eq!(
+            unbounded_select_star_table("SELECT * FROM public.events ORDER BY id"),
+            Some("public.events".to_string())
+        );
+        assert_eq!(unbounded_select_star_table("SELECT * FROM events LIMIT 10"), None);
+        assert_eq!(unbounded_select_star_table("SELECT * FROM events WHERE id = 1"), None);
+        assert_eq!(unbounded_select_star_table("SELECT TOP 5 * FROM events"), None);
+        assert_eq!(unbounded_select_star_table("SELECT id FROM events"), None);
+        assert_eq!(unbounded_select_star_table("SELECT * FROM a, b"), None);
+    }
+
+    #[test]
+    fn test_trim_trailing_semicolon() {
+        assert_eq!(trim_trailing_semicolon("SELECT 1;"), "SELECT 1");
+        assert_eq!(trim_trailing_semicolon("  SELECT 1; \n"), "SELECT 1");
+        assert_eq!(trim_trailing_semicolon("SELECT 1"), "SELECT 1");
+
+        let script = trim_trailing_semicolon("SELECT 1; SELECT 2;");
+        assert_eq!(script, "SELECT 1; SELECT 2");
+        assert_eq!(split_statements(script), vec!["SELECT 1", "SELECT 2"]);
+        assert_eq!(split_statements("SELECT 1; ").len(), 1);
+        assert_eq!(unbounded_select_star_table("SELECT * FROM events; "), Some("events".to_string()));
+    }
+
+    #[test]
+    fn test_trim_trailing_semicolon_keeps_plsql_end() {
+        let block = "BEGIN\n  UPDATE t SET x = 1;\nEND;";
+        assert_eq!(trim_trailing_semicolon(block), block);
+        let declare = "DECLARE n NUMBER; BEGIN SELECT 1 INTO n FROM dual; END;";
+        assert_eq!(trim_trailing_semicolon(declare), declare);
+        let procedure = "CREATE OR REPLACE PROCEDURE touch IS BEGIN NULL; END touch; ";
+        assert_eq!(trim_trailing_semicolon(procedure), procedure.trim());
+
+        assert_eq!(trim_trailing_semicolon("BEGIN;"), "BEGIN");
+        assert_eq!(trim_trailing_semicolon("SELECT CASE WHEN a THEN 1 END;"), "SELECT CASE WHEN a THEN 1 END");
+        assert_eq!(
+            trim_trailing_semicolon("CREATE VIEW v AS SELECT CASE WHEN a THEN 1 END;"),
+            "CREATE VIEW v AS SELECT CASE WHEN a THEN 1 END"
+        );
+    }
+
+    #[test]
+    fn test_split_simple_statements() {
+        let statements = split_statements("SELECT 1; SELECT 2;\n\nSELECT 3");
+        assert_eq!(statements, vec!["SELECT 1", "SELECT 2", "SELECT 3"]);
+    }
+
+    #[test]
+    fn test_split_ignores_semicolons_in_strings_and_comments() {
+        let script = "INSERT INTO t VALUES ('a;b', 'it''s');\n-- comment; here\nSELECT \"x;y\" FROM t; /* ; */";
+        let statements = split_statements(script);
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[0], "INSERT INTO t VALUES ('a;b', 'it''s')");
+        assert!(statements[1].ends_with("SELECT \"x;y\" FROM t"));
+    }
+
+    #[test]
+    fn test_classify_distinguishes_upserts_and_merge() {
+        assert_eq!(
+            classify_statement("INSERT INTO users (id, name) VALUES (1, 'a') ON CONFLICT (id) DO UPDATE SET name = EXCLUDED.name"),
+            StatementKind::Upsert
+        );
+        assert_eq!(
+            classify_statement("insert into users (id) values (1) on duplicate key update id = id"),
+            StatementKind::Upsert
+        );
+        assert_eq!(
+            classify_statement("INSERT INTO notes (body) VALUES ('on conflict')"),
+            StatementKind::Insert
+        );
+        assert_eq!(
+            classify_statement("MERGE INTO users u USING staging s ON u.id = s.id WHEN MATCHED THEN UPDATE SET name = s.name"),
+            StatementKind::Merge
+        );
+
+        for sql in [
+            "INSERT INTO users (id) VALUES (1) ON CONFLICT DO NOTHING",
+            "INSERT INTO users (id) VALUES (1)",
+            "MERGE INTO users USING staging ON users.id = staging.id WHEN MATCHED THEN DELETE",
+        ] {
+            assert!(classify_statement(sql).is_mutating());
+            assert!(!is_unguarded_destructive(sql));
+        }
+    }
+
+    #[test]
+    fn test_classify_explain_analyze_uses_inner_statement() {
+        assert_eq!(classify_statement("EXPLAIN DELETE FROM users"), StatementKind::Read);
+        assert_eq!(
+            classify_statement("EXPLAIN ANALYZE DELETE FROM users"),
+            StatementKind::Delete
+        );
+        assert_eq!(
+            classify_statement("EXPLAIN (ANALYZE, BUFFERS) UPDATE users SET name = 'x'"),
+            StatementKind::Update
+        );
+        assert_eq!(
+            classify_statement("EXPLAIN (ANALYZE, BUFFERS) SELECT * FROM users"),
+            StatementKind::Read
+        );
+    }
+
+    #[test]
+    fn test_classify_looks_past_ctes() {
+        assert_eq!(
+            classify_statement("WITH recent AS (SELECT id FROM orders WHERE total > 10) SELECT * FROM recent"),
+            StatementKind::Read
+        );
+        assert_eq!(
+            classify_statement("WITH stale AS (SELECT id FROM sessions) DELETE FROM sessions WHERE id IN (SELECT id FROM stale)"),
+            StatementKind::Delete
+        );
+        assert_eq!(
+            classify_statement(
+                "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 5), \
+                 \"Totals\" (k) AS MATERIALIZED (SELECT 1) \
+                 INSERT INTO t (id) SELECT i FROM n"
+            ),
+            StatementKind::Insert
+        );
+        // A CTE nested inside another, then a read or a write
+        assert_eq!(
+            classify_statement(
+                "WITH outer_cte AS (WITH inner_cte AS (SELECT 1 AS x) SELECT x FROM inner_cte) \
+                 SELECT * FROM outer_cte"
+            ),
+            StatementKind::Read
+        );
+        assert_eq!(
+            classify_statement(
+                "WITH outer_cte AS (WITH inner_cte AS (SELECT 1 AS x) SELECT x FROM inner_cte) \
+                 UPDATE t SET v = 1 WHERE id IN (SELECT x FROM outer_cte)"
+            ),
+            StatementKind::Update
+        );
+        // A data-modifying CTE changes data even when the main statement reads
+        assert!(classify_statement(
+            "WITH gone AS (DELETE FROM logs RETURNING *) SELECT count(*) FROM gone"
+        )
+        .is_mutating());
+        assert!(classify_statement("WITH broken AS SELECT 1").is_mutating());
+
+        // Parenthesized reads, alone or as the main statement after CTEs
+        assert_eq!(classify_statement("(SELECT 1) UNION (SELECT 2)"), StatementKind::Read);
+        assert_eq!(classify_statement("((SELECT id FROM users))"), StatementKind::Read);
+        assert_eq!(
+            classify_statement("WITH a AS (SELECT 1 AS x) (SELECT x FROM a) UNION (SELECT 2)"),
+            StatementKind::Read
+        );
+        assert_eq!(classify_statement("(DELETE FROM users)"), StatementKind::Delete);
+
+        // Only a WHERE on the main statement guards it
+        assert!(is_unguarded_destructive(
+            "WITH keep AS (SELECT id FROM users WHERE active) DELETE FROM users"
+        ));
+        assert!(!is_unguarded_destructive(
+            "WITH keep AS (SELECT id FROM users) DELETE FROM users WHERE id NOT IN (SELECT id FROM keep)"
+        ));
+    }
+
+    #[test]
+    fn test_unguarded_destructive_statements() {
+        assert!(is_unguarded_destructive("DELETE FROM users"));
+        assert!(is_unguarded_destructive("UPDATE users SET active = 0 -- WHERE id = 1"));
+        assert!(is_unguarded_destructive("DROP TABLE users"));
+        assert!(!is_unguarded_destructive("DELETE FROM users WHERE id = 1"));
+        assert!(!is_unguarded_destructive("SELECT * FROM users"));
+        assert!(!classify_statement("SELECT 'DELETE FROM users'").is_mutating());
+    }
+
+    #[test]
+    fn test_split_keeps_dollar_quoted_bodies() {
+        let script = "CREATE FUNCTION f() RETURNS int AS $body$ SELECT 1; $body$ LANGUAGE sql; SELECT $1";
+        let statements = split_statements(script);
+        assert_eq!(statements.len(), 2);
+        assert!(statements[0].contains("SELECT 1; $body$"));
+        assert_eq!(statements[1], "SELECT $1");
+    }
+
+    #[test]
+    fn test_referenced_identifiers_resolve_aliases() {
+        let references = referenced_identifiers(
+            "SELECT u.name, o.total FROM public.users u, \"Line Items\" li \
+             JOIN orders AS o ON o.user_id = u.id \
+             WHERE EXTRACT(YEAR FROM o.created_at) = 2024 AND u.name <> 'FROM ghosts'",
+        );
+        assert_eq!(references.tables, ["public.users", "Line Items", "orders"]);
+        for (table, column) in [
+            ("public.users", "name"),
+            ("orders", "total"),
+            ("orders", "user_id"),
+            ("public.users", "id"),
+            ("orders", "created_at"),
+        ] {
+            assert!(references.columns.contains(&(table.to_string(), column.to_string())));
+        }
+    }
+
+    #[test]
+    fn test_referenced_identifiers_skip_ctes_and_subqueries() {
+        let references = referenced_identifiers(
+            "WITH recent AS (SELECT id FROM orders) \
+             SELECT r.id, s.n FROM recent r JOIN (SELECT count(*) AS n FROM users) s ON true \
+             CROSS JOIN generate_series(1, 3) g",
+        );
+        assert_eq!(references.tables, ["orders", "users"]);
+        assert!(references.columns.is_empty());
+    }
+}
@@ -0,0 +1,298 @@
+//! Template Queries
+//!
+//! Built-in catalog queries for common questions (row counts, table sizes,
+//! recent changes) that run without asking the LLM. Each template holds the
+//! SQL for every backend that can answer it.
+//!
+//! Values are cast to text because result rows are decoded as strings.
+
+use crate::database::connection::DatabaseBackend;
+use crate::database::schema::{quote_identifier, SchemaIndex};
+use crate::error::{Result, SchemaForgeError};
+
+/// Which question a template answers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TemplateKind {
+    Counts,
+    Sizes,
+    Largest,
+    Recent,
+}
+
+/// A named built-in query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryTemplate {
+    /// Name used with `/template <name>`
+    pub name: &'static str,
+    /// Description shown by `/templates`
+    pub description: &'static str,
+    kind: TemplateKind,
+}
+
+static TEMPLATES: &[QueryTemplate] = &[
+    QueryTemplate {
+        name: "counts",
+        description: "Row count per table (from statistics except on SQLite)",
+        kind: TemplateKind::Counts,
+    },
+    QueryTemplate {
+        name: "sizes",
+        description: "On-disk size of each table in bytes",
+        kind: TemplateKind::Sizes,
+    },
+    QueryTemplate {
+        name: "largest",
+        description: "The ten largest tables by size",
+        kind: TemplateKind::Largest,
+    },
+    QueryTemplate {
+        name: "recent",
+        description: "The ten most recently modified tables",
+        kind: TemplateKind::Recent,
+    },
+];
+
+impl QueryTemplate {
+    /// SQL for this template on a backend, or `None` if the backend cannot answer it
+    ///
+    /// SQLite has no row-count statistics, so `counts` is built from the
+    /// tables in `schema_index`.
+    pub fn sql(&self, backend: DatabaseBackend, schema_index: &SchemaIndex) -> Option<String> {
+        match self.kind {
+            TemplateKind::Counts => counts_sql(backend, schema_index),
+            TemplateKind::Sizes => Some(sizes_sql(backend, false)),
+            TemplateKind::Largest => Some(sizes_sql(backend, true)),
+            TemplateKind::Recent => recent_sql(backend),
+        }
+    }
+}
+
+/// All built-in templates, in listing order
+pub fn templates() -> &'static [QueryTemplate] {
+    TEMPLATES
+}
+
+/// Look up a template by name, ignoring case
+pub fn find_template(name: &str) -> Option<&'static QueryTemplate> {
+    TEMPLATES
+        .iter()
+        .find(|template| template.name.eq_ignore_ascii_case(name))
+}
+
+/// Resolve a template name to SQL for the connected backend
+pub fn template_sql(
+    name: &str,
+    backend: DatabaseBackend,
+    schema_index: &SchemaIndex,
+) -> Result<String> {
+    let template = find_template(name).ok_or_else(|| {
+        SchemaForgeError::InvalidInput(format!(
+            "Unknown template: '{}'. Available: {}",
+            name,
+            TEMPLATES
+                .iter()
+                .map(|template| template.name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    })?;
+
+    template.sql(backend, schema_index).ok_or_else(|| {
+        SchemaForgeError::InvalidInput(format!(
+            "The '{}' template is not available for {:?} databases",
+            template.name, backend
+        ))
+    })
+}
+
+/// Format the `/templates` listing
+pub fn format_templates() -> String {
+    let mut output = String::from("Template queries (run with /template <name>):\n");
+    for template in TEMPLATES {
+        output.push_str(&format!("  {:<10} {}\n", template.name, template.description));
+    }
+    output
+}
+
+fn counts_sql(backend: DatabaseBackend, schema_index: &SchemaIndex) -> Option<String> {
+    match backend {
+        DatabaseBackend::PostgreSQL => Some(
+            "SELECT schemaname || '.' || relname AS table_name, n_live_tup::text AS row_count \
+             FROM pg_stat_user_tables ORDER BY table_name"
+                .to_string(),
+        ),
+        DatabaseBackend::MySQL => Some(
+            "SELECT table_name, CAST(table_rows AS CHAR) AS row_count \
+             FROM information_schema.tables \
+             WHERE table_schema = DATABASE() AND table_type = 'BASE TABLE' \
+             ORDER BY table_name"
+                .to_string(),
+        ),
+        DatabaseBackend::Oracle => Some(
+            "SELECT table_name, TO_CHAR(num_rows) AS row_count FROM user_tables ORDER BY table_name"
+                .to_string(),
+        ),
+        DatabaseBackend::MSSQL => Some(
+            "SELECT s.name + '.' + t.name AS table_name, CAST(SUM(p.rows) AS NVARCHAR(20)) AS row_count \
+             FROM sys.tables t \
+             JOIN sys.schemas s ON s.schema_id = t.schema_id \
+             JOIN sys.partitions p ON p.object_id = t.object_id AND p.index_id IN (0, 1) \
+             GROUP BY s.name, t.name ORDER BY table_name"
+                .to_string(),
+        ),
+        DatabaseBackend::SQLite => {
+            let selects: Vec<String> = schema_index
+                .tables_only()
+                .iter()
+                .map(|table| {
+                    let from = match table.schema {
+                        Some(ref schema) => format!(
+                            "{}.{}",
+                            quote_identifier(schema, backend),
+                            quote_identifier(&table.name, backend)
+                        ),
+                        None => quote_identifier(&table.name, backend),
+                    };
+                    format!(
+                        "SELECT '{}' AS table_name, CAST(COUNT(*) AS TEXT) AS row_count FROM {}",
+                        table.qualified_name().replace('\'', "''"),
+                        from
+                    )
+                })
+                .collect();
+            if selects.is_empty() {
+                return None;
+            }
+            Some(format!("{} ORDER BY table_name", selects.join(" UNION ALL ")))
+        }
+    }
+}
+
+fn sizes_sql(backend: DatabaseBackend, largest: bool) -> String {
+    match backend {
+        DatabaseBackend::PostgreSQL => format!(
+            "SELECT schemaname || '.' || relname AS table_name, \
+             pg_total_relation_size(relid)::text AS bytes, \
+             pg_size_pretty(pg_total_relation_size(relid)) AS size \
+             FROM pg_statio_user_tables {}",
+            if largest {
+                "ORDER BY pg_total_relation_size(relid) DESC LIMIT 10"
+            } else {
+                "ORDER BY table_name"
+            }
+        ),
+        DatabaseBackend::MySQL => format!(
+            "SELECT table_name, CAST(data_length + index_length AS CHAR) AS bytes \
+             FROM information_schema.tables \
+             WHERE table_schema = DATABASE() AND table_type = 'BASE TABLE' {}",
+            if largest {
+                "ORDER BY data_length + index_length DESC LIMIT 10"
+            } else {
+                "ORDER BY table_name"
+            }
+        ),
+        DatabaseBackend::SQLite => format!(
+            "SELECT name AS table_name, CAST(SUM(pgsize) AS TEXT) AS bytes \
+             FROM dbstat WHERE name NOT LIKE 'sqlite_%' GROUP BY name {}",
+            if largest {
+                "ORDER BY SUM(pgsize) DESC LIMIT 10"
+            } else {
+                "ORDER BY table_name"
+            }
+        ),
+        DatabaseBackend::Oracle => format!(
+            "SELECT segment_name AS table_name, TO_CHAR(SUM(bytes)) AS bytes \
+             FROM user_segments WHERE segment_type LIKE 'TABLE%' GROUP BY segment_name {}",
+            if largest {
+                "ORDER BY SUM(bytes) DESC FETCH FIRST 10 ROWS ONLY"
+            } else {
+                "ORDER BY table_name"
+            }
+        ),
+        DatabaseBackend::MSSQL => format!(
+            "SELECT s.name + '.' + t.name AS table_name, \
+             CAST(SUM(ps.used_page_count) * 8192 AS NVARCHAR(20)) AS bytes \
+             FROM sys.dm_db_partition_stats ps \
+             JOIN sys.tables t ON t.object_id = ps.object_id \
+             JOIN sys.schemas s ON s.schema_id = t.schema_id \
+             GROUP BY s.name, t.name {}",
+            if largest {
+                "ORDER BY SUM(ps.used_page_count) DESC OFFSET 0 ROWS FETCH NEXT 10 ROWS ONLY"
+            } else {
+                "ORDER BY table_name"
+            }
+        ),
+    }
+}
+
+fn recent_sql(backend: DatabaseBackend) -> Option<String> {
+    match backend {
+        DatabaseBackend::MySQL => Some(
+            "SELECT table_name, CAST(update_time AS CHAR) AS last_modified \
+             FROM information_schema.tables \
+             WHERE table_schema = DATABASE() AND update_time IS NOT NULL \
+             ORDER BY update_time DESC LIMIT 10"
+                .to_string(),
+        ),
+        DatabaseBackend::Oracle => Some(
+            "SELECT table_name, TO_CHAR(timestamp, 'YYYY-MM-DD HH24:MI:SS') AS last_modified \
+             FROM user_tab_modifications \
+             ORDER BY timestamp DESC FETCH FIRST 10 ROWS ONLY"
+                .to_string(),
+        ),
+        DatabaseBackend::MSSQL => Some(
+            "SELECT OBJECT_SCHEMA_NAME(object_id) + '.' + OBJECT_NAME(object_id) AS table_name, \
+             CONVERT(NVARCHAR(30), MAX(last_user_update), 120) AS last_modified \
+             FROM sys.dm_db_index_usage_stats \
+             WHERE database_id = DB_ID() AND last_user_update IS NOT NULL \
+             GROUP BY object_id \
+             ORDER BY MAX(last_user_update) DESC OFFSET 0 ROWS FETCH NEXT 10 ROWS ONLY"
+                .to_string(),
+        ),
+        // Neither keeps a per-table modification time
+        DatabaseBackend::PostgreSQL | DatabaseBackend::SQLite => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::schema::Table;
+
+    #[test]
+    fn test_template_sql_is_backend_specific() {
+        let index = SchemaIndex::new();
+        let postgres = template_sql("sizes", DatabaseBackend::PostgreSQL, &index).unwrap();
+        assert!(postgres.contains("pg_total_relation_size"));
+        let mysql = template_sql("SIZES", DatabaseBackend::MySQL, &index).unwrap();
+        assert!(mysql.contains("information_schema.tables"));
+        let sqlite = template_sql("largest", DatabaseBackend::SQLite, &index).unwrap();
+        assert!(sqlite.contains("dbstat") && sqlite.contains("LIMIT 10"));
+
+        let counts = template_sql("counts", DatabaseBackend::Oracle, &index).unwrap();
+        assert!(counts.contains("user_tables"));
+    }
+
+    #[test]
+    fn test_sqlite_counts_use_indexed_tables() {
+        let mut index = SchemaIndex::new();
+        assert!(template_sql("counts", DatabaseBackend::SQLite, &index).is_err());
+
+        index.add_table(Table::new("orders"));
+        index.add_table(Table::new("users"));
+        index.add_table(Table::new_view("active_users"));
+        let sql = template_sql("counts", DatabaseBackend::SQLite, &index).unwrap();
+        assert!(sql.contains("FROM \"orders\" UNION ALL"));
+        assert!(sql.contains("FROM \"users\""));
+        assert!(!sql.contains("active_users"));
+    }
+
+    #[test]
+    fn test_unknown_or_unsupported_template_errors() {
+        let index = SchemaIndex::new();
+        let err = template_sql("fastest", DatabaseBackend::PostgreSQL, &index).unwrap_err();
+        assert!(err.to_string().contains("counts, sizes, largest, recent"));
+        assert!(template_sql("recent", DatabaseBackend::SQLite, &index).is_err());
+        assert!(format_templates().contains("largest"));
+    }
+}
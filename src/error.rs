@@ -52,6 +52,18 @@ pub enum SchemaForgeError {
         message: String,
     },
 
+    /// Database query errors with a fix suggested by the LLM
+    #[error("{detail}\n\nSuggested fix:\n{suggestion}")]
+    DatabaseQueryDiagnosed {
+        /// The database's own message
+        detail: String,
+        /// The provider's explanation and corrected statement
+        suggestion: String,
+        /// The original query error
+        #[source]
+        source: Box<SchemaForgeError>,
+    },
+
     /// Schema indexing errors
     #[error("Failed to index schema: {0}")]
     SchemaIndexing(String),
@@ -151,6 +163,30 @@ pub enum SchemaForgeError {
     #[error("Rate limit exceeded for provider '{0}'. Try again later.")]
     LLMRateLimitExceeded(String),
 
+    /// LLM returned a reply with no content
+    #[error("{}", empty_response_message(.provider, .reply))]
+    LLMResponseEmpty {
+        /// Provider name
+        provider: String,
+        /// Raw reply text, when the model sent something unusable
+        reply: String,
+    },
+
+    /// LLM reply stopped at its token limit
+    #[error(
+        "The {provider} model's reply was cut off at its token limit ({tokens} tokens), even after \
+         a retry with a larger budget. Ask a narrower question or use a model with a higher max_tokens.{}",
+        quoted_reply("Partial reply", .reply)
+    )]
+    LLMResponseTruncated {
+        /// Provider name
+        provider: String,
+        /// Tokens generated before the cut-off
+        tokens: u32,
+        /// Raw reply text received before the cut-off
+        reply: String,
+    },
+
     /// Command parsing errors
     #[error("Command parsing error: {0}")]
     CommandParse(String),
@@ -201,6 +237,30 @@ pub enum SchemaForgeError {
     Anyhow(#[from] anyhow::Error),
 }
 
+/// Message for [`SchemaForgeError::LLMResponseEmpty`], quoting any raw reply
+fn empty_response_message(provider: &str, reply: &str) -> String {
+    if reply.trim().is_empty() {
+        format!(
+            "The {} model returned an empty response. Try rephrasing the request.",
+            provider
+        )
+    } else {
+        format!(
+            "The model did not return a SQL query; try rephrasing.{}",
+            quoted_reply("Model reply", reply)
+        )
+    }
+}
+
+/// A labelled block quoting the model's reply, or nothing when it is empty
+fn quoted_reply(label: &str, reply: &str) -> String {
+    if reply.trim().is_empty() {
+        String::new()
+    } else {
+        format!("\n\n{}:\n{}", label, reply.trim())
+    }
+}
+
 impl SchemaForgeError {
     /// Create a database connection error
     pub fn db_connection(url: impl Into<String>, source: sqlx::Error) -> Self {
@@ -218,6 +278,20 @@ impl SchemaForgeError {
         }
     }
 
+    /// Check whether a query failed because the connection was lost
+    ///
+    /// Only sqlx errors are classified; SQL errors such as syntax mistakes
+    /// never count as connection loss.
+    pub fn is_connection_lost(&self) -> bool {
+        matches!(
+            self,
+            Self::DatabaseQuery {
+                source: sqlx::Error::PoolClosed | sqlx::Error::Io(_) | sqlx::Error::WorkerCrashed,
+                ..
+            }
+        )
+    }
+
     /// Create a database connection error for drivers outside sqlx
     pub fn db_connection_message(url: impl Into<String>, message: impl Into<String>) -> Self {
         Self::DatabaseConnectionMessage {
@@ -296,6 +370,8 @@ impl SchemaForgeError {
                 | Self::MissingConfig(_)
                 | Self::LLMApiKeyMissing(_)
                 | Self::LLMRateLimitExceeded(_)
+                | Self::LLMResponseEmpty { .. }
+                | Self::LLMResponseTruncated { .. }
                 | Self::InvalidInput(_)
         )
     }
@@ -339,11 +415,45 @@ mod tests {
     }
 
     #[test]
-    fn test_helper_methods() {
-        let err = SchemaForgeError::config("test message");
-        assert_eq!(err.to_string(), "Configuration error: test message");
-
-        let err = SchemaForgeError::missing_config("api_key");
-        assert_eq!(err.to_string(), "Missing configuration: api_key");
+    fn test_llm_response_errors() {
+        let err = SchemaForgeError::LLMResponseEmpty {
+            provider: "Groq".to_string(),
+            reply: String::new(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "The Groq model returned an empty response. Try rephrasing the request."
+        );
+        assert!(err.is_user_facing());
+        assert!(!err.is_retryable());
+
+        let err = SchemaForgeError::LLMResponseEmpty {
+            provider: "Groq".to_string(),
+            reply: "<sql></sql>\n".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "The model did not return a SQL query; try rephrasing.\n\nModel reply:\n<sql></sql>"
+        );
+
+        let err = SchemaForgeError::LLMResponseTruncated {
+            provider: "OpenAI".to_string(),
+            tokens: 8192,
+            reply: String::new(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "The OpenAI model's reply was cut off at its token limit (8192 tokens), even after a retry \
+             with a larger budget. Ask a narrower question or use a model with a higher max_tokens."
+        );
+        assert!(err.is_user_facing());
+        assert!(!err.is_retryable());
+
+        let err = SchemaForgeError::LLMResponseTruncated {
+            provider: "OpenAI".to_string(),
+            tokens: 8192,
+            reply: "<sql>SELECT id, na".to_string(),
+        };
+        assert!(err.to_string().ends_with("max_tokens.\n\nPartial reply:\n<sql>SELECT id, na"));
     }
 }
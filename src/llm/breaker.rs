@@ -0,0 +1,196 @@
+//! Session circuit breaker for failing LLM providers
+//!
+//! After several provider failures in a short window, further calls to that
+//! provider are refused until a cooldown passes or `/reset-breaker` is run,
+//! so a broken endpoint does not keep costing time and retries.
+
+use crate::error::{Result, SchemaForgeError};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Consecutive failures that open the breaker
+pub const FAILURE_THRESHOLD: usize = 3;
+/// Failures older than this no longer count toward the threshold
+pub const FAILURE_WINDOW: Duration = Duration::from_secs(120);
+/// How long an open breaker refuses calls
+pub const COOLDOWN: Duration = Duration::from_secs(60);
+
+/// Failure tracking for every provider used this session
+#[derive(Debug, Default)]
+pub struct CircuitBreaker {
+    providers: HashMap<String, ProviderHealth>,
+}
+
+#[derive(Debug, Default)]
+struct ProviderHealth {
+    failures: Vec<Instant>,
+    open_until: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    /// Refuse the call when `provider`'s breaker is open
+    ///
+    /// Once the cooldown has passed the breaker closes and the call is let
+    /// through; the next failure starts counting from zero.
+    pub fn check(&mut self, provider: &str, now: Instant) -> Result<()> {
+        let Some(health) = self.providers.get_mut(provider) else {
+            return Ok(());
+        };
+        match health.open_until {
+            Some(until) if until > now => Err(SchemaForgeError::llm_provider(
+                provider,
+                format!(
+                    "paused after {} failed requests in a row; retrying in {}s, or run /reset-breaker now",
+                    FAILURE_THRESHOLD,
+                    (until - now).as_secs().max(1)
+                ),
+            )),
+            Some(_) => {
+                health.open_until = None;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Forget `provider`'s failures after a successful call
+    pub fn record_success(&mut self, provider: &str) {
+        self.providers.remove(provider);
+    }
+
+    /// Count a failed call, opening the breaker at the threshold
+    ///
+    /// Returns `true` when this failure opened it.
+    pub fn record_failure(&mut self, provider: &str, now: Instant) -> bool {
+        let health = self.providers.entry(provider.to_string()).or_default();
+        health
+            .failures
+            .retain(|failed_at| now.saturating_duration_since(*failed_at) <= FAILURE_WINDOW);
+        health.failures.push(now);
+        if health.failures.len() < FAILURE_THRESHOLD {
+            return false;
+        }
+        health.failures.clear();
+        health.open_until = Some(now + COOLDOWN);
+        true
+    }
+
+    /// Check whether `provider`'s breaker is refusing calls
+    ///
+    /// Calls go through [`CircuitBreaker::check`], which also closes an
+    /// expired breaker; this read-only view is for tests.
+    #[cfg(test)]
+    pub fn is_open(&self, provider: &str, now: Instant) -> bool {
+        self.providers
+            .get(provider)
+            .and_then(|health| health.open_until)
+            .is_some_and(|until| until > now)
+    }
+
+    /// Close the breaker for one provider, or for all of them
+    pub fn reset(&mut self, provider: Option<&str>) {
+        match provider {
+            Some(provider) => {
+                self.providers.remove(provider);
+            }
+            None => self.providers.clear(),
+        }
+    }
+}
+
+/// Check whether an error means the provider itself failed
+///
+/// Only these count toward the breaker; a bad question or database error
+/// says nothing about the provider's health.
+pub fn is_provider_failure(error: &SchemaForgeError) -> bool {
+    matches!(
+        error,
+        SchemaForgeError::LLMApiError { .. }
+            | SchemaForgeError::LLMProvider { .. }
+            | SchemaForgeError::LLMRateLimitExceeded(_)
+            | SchemaForgeError::Http(_)
+            | SchemaForgeError::HttpStatus { .. }
+            | SchemaForgeError::Timeout(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breaker_trips_after_threshold() {
+        let mut breaker = CircuitBreaker::default();
+        let now = Instant::now();
+
+        for _ in 1..FAILURE_THRESHOLD {
+            assert!(!breaker.record_failure("openai", now));
+            assert!(breaker.check("openai", now).is_ok());
+        }
+        assert!(breaker.record_failure("openai", now));
+        assert!(breaker.is_open("openai", now));
+
+        let error = breaker.check("openai", now).unwrap_err();
+        assert!(error.to_string().contains("/reset-breaker"));
+        // Other providers are unaffected
+        assert!(breaker.check("groq", now).is_ok());
+    }
+
+    #[test]
+    fn test_breaker_closes_after_cooldown() {
+        let mut breaker = CircuitBreaker::default();
+        let now = Instant::now();
+        for _ in 0..FAILURE_THRESHOLD {
+            breaker.record_failure("openai", now);
+        }
+
+        let later = now + COOLDOWN + Duration::from_secs(1);
+        assert!(!breaker.is_open("openai", later));
+        assert!(breaker.check("openai", later).is_ok());
+        // Counting starts again from zero
+        assert!(!breaker.record_failure("openai", later));
+    }
+
+    #[test]
+    fn test_old_failures_and_successes_do_not_count() {
+        let mut breaker = CircuitBreaker::default();
+        let now = Instant::now();
+        breaker.record_failure("openai", now);
+        breaker.record_failure("openai", now);
+        breaker.record_success("openai");
+        assert!(!breaker.record_failure("openai", now));
+
+        let mut breaker = CircuitBreaker::default();
+        breaker.record_failure("openai", now);
+        breaker.record_failure("openai", now);
+        let later = now + FAILURE_WINDOW + Duration::from_secs(1);
+        assert!(!breaker.record_failure("openai", later));
+    }
+
+    #[test]
+    fn test_manual_reset() {
+        let mut breaker = CircuitBreaker::default();
+        let now = Instant::now();
+        for _ in 0..FAILURE_THRESHOLD {
+            breaker.record_failure("openai", now);
+            breaker.record_failure("groq", now);
+        }
+
+        breaker.reset(Some("openai"));
+        assert!(breaker.check("openai", now).is_ok());
+        assert!(breaker.is_open("groq", now));
+
+        breaker.reset(None);
+        assert!(breaker.check("groq", now).is_ok());
+    }
+
+    #[test]
+    fn test_only_provider_errors_count() {
+        assert!(is_provider_failure(&SchemaForgeError::LLMRateLimitExceeded(
+            "openai".to_string()
+        )));
+        assert!(!is_provider_failure(&SchemaForgeError::InvalidInput(
+            "Not connected".to_string()
+        )));
+    }
+}
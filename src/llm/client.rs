@@ -9,10 +9,13 @@ use reqwest::{Client, StatusCode};
 use serde::Serialize;
 use std::time::Duration;
 use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
 use std::str::FromStr;
+use std::sync::Arc;
 
 /// Default maximum number of retry attempts
-const DEFAULT_MAX_RETRIES: u32 = 3;
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
 
 /// Default initial retry delay in milliseconds
 const DEFAULT_INITIAL_DELAY_MS: u64 = 1000;
@@ -20,6 +23,13 @@ const DEFAULT_INITIAL_DELAY_MS: u64 = 1000;
 /// Default timeout for HTTP requests (in seconds)
 const DEFAULT_TIMEOUT_SECS: u64 = 60;
 
+/// Async hook that returns a fresh bearer token
+///
+/// Used for gateways that issue short-lived OAuth tokens instead of static
+/// API keys. Called once per request attempt.
+pub type TokenProvider =
+    Arc<dyn Fn() -> Pin<Box<dyn Future<Output = Result<String>> + Send>> + Send + Sync>;
+
 /// HTTP client for LLM API requests
 #[derive(Clone)]
 pub struct LLMHttpClient {
@@ -29,6 +39,10 @@ pub struct LLMHttpClient {
     max_retries: u32,
     /// Initial retry delay in milliseconds
     initial_delay_ms: u64,
+    /// Whether 408 Request Timeout responses are retried
+    retry_request_timeout: bool,
+    /// Refreshes the Authorization header per request when set
+    token_provider: Option<TokenProvider>,
 }
 
 impl LLMHttpClient {
@@ -48,6 +62,8 @@ impl LLMHttpClient {
             client,
             max_retries: DEFAULT_MAX_RETRIES,
             initial_delay_ms: DEFAULT_INITIAL_DELAY_MS,
+            retry_request_timeout: false,
+            token_provider: None,
         })
     }
 
@@ -63,6 +79,35 @@ impl LLMHttpClient {
         self
     }
 
+    /// Retry 408 Request Timeout responses
+    ///
+    /// Off by default: a 408 can arrive after the provider has already
+    /// started generating, so re-sending a completion POST may bill twice.
+    pub fn with_request_timeout_retry(mut self, enabled: bool) -> Self {
+        self.retry_request_timeout = enabled;
+        self
+    }
+
+    /// Fetch a bearer token from `provider` before every request
+    ///
+    /// The token replaces any Authorization header built from a static key.
+    pub fn with_token_provider(mut self, provider: TokenProvider) -> Self {
+        self.token_provider = Some(provider);
+        self
+    }
+
+    /// Apply the token provider, if any, to a request's headers
+    async fn authorize(&self, mut headers: HeaderMap) -> Result<HeaderMap> {
+        if let Some(ref provider) = self.token_provider {
+            let token = provider().await?;
+            let value = HeaderValue::from_str(&format!("Bearer {}", token)).map_err(|_| {
+                SchemaForgeError::InvalidHeader("Invalid bearer token format".to_string())
+            })?;
+            headers.insert(AUTHORIZATION, value);
+        }
+        Ok(headers)
+    }
+
     /// Make a POST request with retry logic
     ///
     /// # Arguments
@@ -92,7 +137,7 @@ impl LLMHttpClient {
         let response = self
             .client
             .post(url)
-            .headers(headers.clone())
+            .headers(self.authorize(headers.clone()).await?)
             .json(body)
             .send()
             .await
@@ -132,6 +177,13 @@ impl LLMHttpClient {
     }
 
     /// Check if a request should be retried
+    ///
+    /// Classification:
+    /// - 429 Too Many Requests: retried (the request was rejected unprocessed)
+    /// - 5xx, including 503 Service Unavailable: retried
+    /// - 408 Request Timeout: retried only when enabled with
+    ///   [`with_request_timeout_retry`](Self::with_request_timeout_retry)
+    /// - any other 4xx: never retried, the request itself is at fault
     fn should_retry(&self, status: StatusCode, attempt: u32) -> bool {
         if attempt >= self.max_retries {
             return false;
@@ -147,9 +199,9 @@ impl LLMHttpClient {
             return true;
         }
 
-        // Retry on connection issues (timeouts, etc.)
-        if status == StatusCode::REQUEST_TIMEOUT || status == StatusCode::SERVICE_UNAVAILABLE {
-            return true;
+        // The request may have been partially processed, so only retry on opt-in
+        if status == StatusCode::REQUEST_TIMEOUT {
+            return self.retry_request_timeout;
         }
 
         false
@@ -199,6 +251,13 @@ impl LLMHttpClient {
         headers.insert(key_header, value_header);
         Ok(headers)
     }
+
+    /// Add every custom header to existing headers
+    pub fn add_headers(headers: HeaderMap, custom: &HashMap<String, String>) -> Result<HeaderMap> {
+        custom
+            .iter()
+            .try_fold(headers, |headers, (key, value)| Self::add_header(headers, key, value))
+    }
 }
 
 impl Default for LLMHttpClient {
@@ -273,6 +332,21 @@ mod tests {
 
         // Should not retry after max attempts
         assert!(!client.should_retry(StatusCode::INTERNAL_SERVER_ERROR, 5));
+
+        // With retries off even the first failure is final
+        let client = client.with_max_retries(0);
+        assert!(!client.should_retry(StatusCode::SERVICE_UNAVAILABLE, 0));
+        assert!(!client.should_retry(StatusCode::TOO_MANY_REQUESTS, 0));
+    }
+
+    #[test]
+    fn test_request_timeout_retry_is_opt_in() {
+        let client = LLMHttpClient::new().unwrap();
+        assert!(!client.should_retry(StatusCode::REQUEST_TIMEOUT, 0));
+
+        let client = client.with_request_timeout_retry(true);
+        assert!(client.should_retry(StatusCode::REQUEST_TIMEOUT, 0));
+        assert!(!client.should_retry(StatusCode::REQUEST_TIMEOUT, DEFAULT_MAX_RETRIES));
     }
 
     #[test]
@@ -294,6 +368,30 @@ mod tests {
         assert_eq!(headers.get("authorization").unwrap(), "Bearer test-key");
     }
 
+    #[tokio::test]
+    async fn test_token_provider_refreshes_authorization() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&calls);
+        let provider: TokenProvider = Arc::new(move || {
+            let n = counter.fetch_add(1, Ordering::SeqCst) + 1;
+            Box::pin(async move { Ok(format!("token-{}", n)) })
+        });
+
+        let static_headers = LLMHttpClient::build_headers("static-key");
+        let client = LLMHttpClient::new().unwrap();
+        let headers = client.authorize(static_headers.clone()).await.unwrap();
+        assert_eq!(headers.get("authorization").unwrap(), "Bearer static-key");
+
+        let client = client.with_token_provider(provider);
+        let first = client.authorize(static_headers.clone()).await.unwrap();
+        assert_eq!(first.get("authorization").unwrap(), "Bearer token-1");
+        let second = client.authorize(static_headers).await.unwrap();
+        assert_eq!(second.get("authorization").unwrap(), "Bearer token-2");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
     #[test]
     fn test_request_body_builder() {
         let body = RequestBody::new()
@@ -3,8 +3,11 @@
 //! This module provides trait-based LLM provider abstraction
 //! and implementations for various AI services.
 
+pub mod breaker;
 pub mod client;
 pub mod provider;
+pub mod provider_registry;
+pub mod tokens;
 
 // Provider implementations
 pub mod providers {
@@ -14,6 +17,7 @@ pub mod providers {
     pub mod minimax;
     pub mod ollama;
     pub mod openai;
+    pub mod openai_compatible;
     pub mod qwen;
     pub mod xai;
     pub mod zai;
@@ -6,6 +6,7 @@
 use crate::error::{Result, SchemaForgeError};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// LLM message role
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -83,6 +84,16 @@ impl LLMResponse {
         }
     }
 
+    /// Whether generation stopped at the token limit rather than finishing
+    ///
+    /// OpenAI-compatible APIs report `length`, Anthropic `max_tokens` and
+    /// Cohere `MAX_TOKENS`.
+    pub fn is_truncated(&self) -> bool {
+        self.finish_reason.as_deref().is_some_and(|reason| {
+            reason.eq_ignore_ascii_case("length") || reason.eq_ignore_ascii_case("max_tokens")
+        })
+    }
+
     /// Get total token count if available
     pub fn get_total_tokens(&self) -> Option<u32> {
         self.total_tokens
@@ -93,6 +104,145 @@ impl LLMResponse {
     }
 }
 
+/// Deserialize token usage without letting it fail the response
+///
+/// Use with `#[serde(default, deserialize_with = "lenient_usage")]`: a
+/// missing, `null` or unexpectedly shaped value becomes `None`, so the
+/// content of a successful response is still returned.
+pub fn lenient_usage<'de, D, T>(deserializer: D) -> std::result::Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(serde_json::from_value(value).ok())
+}
+
+/// Look up a model's context window in a `(model prefix, tokens)` table
+///
+/// The longest matching prefix wins, so `gpt-4o` is not read as `gpt-4`.
+pub fn lookup_context_window(table: &[(&str, u32)], model: &str) -> Option<u32> {
+    let model = model.to_lowercase();
+    table
+        .iter()
+        .filter(|(prefix, _)| model.starts_with(prefix))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, tokens)| *tokens)
+}
+
+/// Strip markdown code fences from a model's SQL reply
+///
+/// Models often wrap SQL in a ```` ```sql ```` block despite being asked not
+/// to. Text before the opening fence (such as "Here is the query:"), a
+/// language tag on the fence line and anything after the closing fence are
+/// dropped. A reply wrapped in a single backtick pair loses that pair;
+/// backticks inside the SQL, such as MySQL-quoted identifiers, are kept.
+pub fn strip_sql_fences(content: &str) -> String {
+    let content = content.trim();
+    let Some(open) = content.find("```") else {
+        return strip_inline_backticks(content).to_string();
+    };
+
+    let mut body = &content[open + 3..];
+    let (first_line, rest) = body.split_once('\n').unwrap_or((body, ""));
+    let tag = first_line.trim();
+    if !tag.is_empty() && tag.chars().all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '+')) {
+        body = rest;
+    }
+    if let Some(close) = body.find("```") {
+        body = &body[..close];
+    }
+    body.trim().to_string()
+}
+
+/// Drop one backtick pair that wraps the whole text, as in `` `SELECT 1` ``
+///
+/// A backtick-quoted identifier at either end (`` SELECT * FROM `users` ``)
+/// leaves other backticks inside the text, so it is not treated as a wrapper.
+fn strip_inline_backticks(content: &str) -> &str {
+    match content.strip_prefix('`').and_then(|inner| inner.strip_suffix('`')) {
+        Some(inner) if !inner.contains('`') => inner.trim(),
+        _ => content,
+    }
+}
+
+/// Combine the text of every choice in an OpenAI-compatible response
+///
+/// The first choice must carry text content; a `None` there usually means the
+/// model answered with a tool/function call, which is reported as an error
+/// instead of an empty string. Additional non-empty choices (`n > 1`) are
+/// appended, separated by blank lines.
+pub fn combine_choice_contents<'a>(
+    provider: &str,
+    contents: impl IntoIterator<Item = Option<&'a str>>,
+) -> Result<String> {
+    let mut contents = contents.into_iter();
+    let first = match contents.next() {
+        Some(Some(text)) => text,
+        Some(None) => {
+            return Err(SchemaForgeError::llm_provider(
+                provider,
+                "Response contained no text content (the model may have returned a tool or function call)",
+            ))
+        }
+        None => {
+            return Err(SchemaForgeError::LLMResponseEmpty {
+                provider: provider.to_string(),
+                reply: String::new(),
+            })
+        }
+    };
+
+    let mut parts = vec![first];
+    parts.extend(contents.flatten().filter(|text| !text.trim().is_empty()));
+    Ok(parts.join("\n\n"))
+}
+
+/// Fold system messages into the first user message
+///
+/// For APIs without a system role. System texts are joined in order and
+/// placed, followed by a blank line, before the first user message; with no
+/// user message they become one.
+pub fn fold_system_messages(messages: &[Message]) -> Vec<Message> {
+    let (system, mut folded) = split_system_messages(messages);
+    let Some(system) = system else {
+        return folded;
+    };
+    match folded.iter_mut().find(|message| message.role == MessageRole::User) {
+        Some(first_user) => first_user.content = format!("{}\n\n{}", system, first_user.content),
+        None => folded.insert(0, Message::user(system)),
+    }
+    folded
+}
+
+/// Separate the joined system text from the rest of the conversation
+///
+/// For APIs that take the system prompt as its own request field.
+pub fn split_system_messages(messages: &[Message]) -> (Option<String>, Vec<Message>) {
+    let system: Vec<&str> = messages
+        .iter()
+        .filter(|message| message.role == MessageRole::System)
+        .map(|message| message.content.as_str())
+        .collect();
+    let rest = messages
+        .iter()
+        .filter(|message| message.role != MessageRole::System)
+        .cloned()
+        .collect();
+    ((!system.is_empty()).then(|| system.join("\n\n")), rest)
+}
+
+/// Messages as `provider` should receive them
+///
+/// Unchanged when it supports the system role, folded otherwise.
+pub fn prepare_messages<P: LLMProvider + ?Sized>(provider: &P, messages: &[Message]) -> Vec<Message> {
+    if provider.supports_system_role() {
+        messages.to_vec()
+    } else {
+        fold_system_messages(messages)
+    }
+}
+
 /// LLM generation parameters
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GenerationParams {
@@ -143,6 +293,36 @@ impl GenerationParams {
         self.temperature = Some(temperature);
         self
     }
+
+    /// Set top-p sampling
+    pub fn with_top_p(mut self, top_p: f32) -> Self {
+        self.top_p = Some(top_p);
+        self
+    }
+
+    /// Set top-k sampling
+    pub fn with_top_k(mut self, top_k: u32) -> Self {
+        self.top_k = Some(top_k);
+        self
+    }
+
+    /// Set stop sequences
+    pub fn with_stop_sequences(mut self, stop_sequences: Vec<String>) -> Self {
+        self.stop_sequences = Some(stop_sequences);
+        self
+    }
+
+    /// Set presence penalty
+    pub fn with_presence_penalty(mut self, presence_penalty: f32) -> Self {
+        self.presence_penalty = Some(presence_penalty);
+        self
+    }
+
+    /// Set frequency penalty
+    pub fn with_frequency_penalty(mut self, frequency_penalty: f32) -> Self {
+        self.frequency_penalty = Some(frequency_penalty);
+        self
+    }
 }
 
 /// Trait for LLM providers
@@ -216,15 +396,39 @@ pub trait LLMProvider: Send + Sync {
         let response = self
             .generate_with_schema(schema_context, natural_language_query, None)
             .await?;
-        Ok(response.content)
+        Ok(strip_sql_fences(&response.content))
     }
 
     /// Get the provider name
     fn provider_name(&self) -> &str;
 
+    /// Get the model this provider sends requests to
+    fn model_name(&self) -> &str;
+
+    /// Context window of the model in tokens, if known
+    fn max_context_tokens(&self) -> Option<u32> {
+        None
+    }
+
+    /// Whether the API accepts system messages, as a role or its own field
+    ///
+    /// When `false`, [`prepare_messages`] folds them into the first user
+    /// message.
+    fn supports_system_role(&self) -> bool {
+        true
+    }
+
     /// Check if the provider has an API key configured
     fn has_api_key(&self) -> bool;
 
+    /// Send these headers with every request, alongside content type and auth
+    ///
+    /// Providers that cannot send extra headers ignore them.
+    fn set_custom_headers(&mut self, _headers: HashMap<String, String>) {}
+
+    /// Retry a failed request at most this many times (0 fails on the first error)
+    fn set_max_retries(&mut self, _max_retries: u32) {}
+
     /// Validate the provider configuration
     fn validate_config(&self) -> Result<()> {
         if !self.has_api_key() {
@@ -326,6 +530,33 @@ mod tests {
         assert_eq!(assistant_msg.role, MessageRole::Assistant);
     }
 
+    #[test]
+    fn test_strip_sql_fences() {
+        // A single fenced block with a language tag
+        assert_eq!(strip_sql_fences("```sql\nSELECT id FROM users;\n```"), "SELECT id FROM users;");
+        // A fence with no language tag
+        assert_eq!(strip_sql_fences("```\nSELECT 1\n```\n"), "SELECT 1");
+        // A lead-in line before the fence, and a note after it
+        assert_eq!(
+            strip_sql_fences("Here is the query:\n\n```SQL\nSELECT *\nFROM orders\n```\nIt lists every order."),
+            "SELECT *\nFROM orders"
+        );
+        // Stray backticks and inline fences
+        assert_eq!(strip_sql_fences("`SELECT 1`"), "SELECT 1");
+        assert_eq!(strip_sql_fences("```SELECT 1```"), "SELECT 1");
+        // An unclosed fence keeps everything after it
+        assert_eq!(strip_sql_fences("```postgresql\nSELECT now()"), "SELECT now()");
+        // Plain SQL is only trimmed
+        assert_eq!(strip_sql_fences("  SELECT 1\n"), "SELECT 1");
+        // Backtick-quoted identifiers survive, fenced or not
+        assert_eq!(strip_sql_fences("SELECT * FROM `users`"), "SELECT * FROM `users`");
+        assert_eq!(strip_sql_fences("`id` FROM `users`"), "`id` FROM `users`");
+        assert_eq!(
+            strip_sql_fences("```sql\nSELECT `id` FROM `users`\n```"),
+            "SELECT `id` FROM `users`"
+        );
+    }
+
     #[test]
     fn test_generation_params_default() {
         let params = GenerationParams::new();
@@ -343,6 +574,26 @@ mod tests {
         assert_eq!(params.temperature, Some(0.5));
     }
 
+    #[test]
+    fn test_generation_params_full_builder() {
+        let params = GenerationParams::new()
+            .with_max_tokens(512)
+            .with_temperature(0.2)
+            .with_top_p(0.9)
+            .with_top_k(40)
+            .with_stop_sequences(vec![";".to_string()])
+            .with_presence_penalty(0.5)
+            .with_frequency_penalty(1.5);
+
+        assert_eq!(params.max_tokens, Some(512));
+        assert_eq!(params.temperature, Some(0.2));
+        assert_eq!(params.top_p, Some(0.9));
+        assert_eq!(params.top_k, Some(40));
+        assert_eq!(params.stop_sequences, Some(vec![";".to_string()]));
+        assert_eq!(params.presence_penalty, Some(0.5));
+        assert_eq!(params.frequency_penalty, Some(1.5));
+    }
+
     #[test]
     fn test_llm_response() {
         let response = LLMResponse::new("SELECT * FROM users;");
@@ -360,6 +611,47 @@ mod tests {
         assert_eq!(response_with_tokens.get_total_tokens(), Some(15));
     }
 
+    #[test]
+    fn test_fold_system_messages_into_first_user_message() {
+        let messages = vec![
+            Message::system("Use SQLite."),
+            Message::user("How many users?"),
+            Message::assistant("42"),
+            Message::system("Be brief."),
+            Message::user("And orders?"),
+        ];
+
+        let folded = fold_system_messages(&messages);
+        assert_eq!(folded.len(), 3);
+        assert!(folded.iter().all(|message| message.role != MessageRole::System));
+        assert_eq!(folded[0].content, "Use SQLite.\n\nBe brief.\n\nHow many users?");
+        assert_eq!(folded[2].content, "And orders?");
+
+        let folded = fold_system_messages(&[Message::system("Only system")]);
+        assert_eq!(folded.len(), 1);
+        assert_eq!(folded[0].role, MessageRole::User);
+    }
+
+    #[test]
+    fn test_prepare_messages_follows_system_role_support() {
+        use crate::llm::providers::cohere::CohereProvider;
+        use crate::llm::providers::openai;
+
+        let messages = vec![Message::system("Use SQLite."), Message::user("Count users")];
+
+        let openai = openai::provider("key", None);
+        assert!(openai.supports_system_role());
+        let prepared = prepare_messages(&openai, &messages);
+        assert_eq!(prepared[0].role, MessageRole::System);
+        assert_eq!(prepared.len(), 2);
+
+        let cohere = CohereProvider::new("key", None);
+        assert!(!cohere.supports_system_role());
+        let prepared = prepare_messages(&cohere, &messages);
+        assert_eq!(prepared.len(), 1);
+        assert_eq!(prepared[0].content, "Use SQLite.\n\nCount users");
+    }
+
     #[test]
     fn test_provider_builder() {
         let builder = LLMProviderBuilder::new()
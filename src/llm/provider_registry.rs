@@ -0,0 +1,249 @@
+//! Provider Registry
+//!
+//! Every built-in LLM provider is described once here. Provider creation,
+//! default models and the `/providers` listing all read from this table, so
+//! adding a provider means adding one entry.
+
+use crate::error::{Result, SchemaForgeError};
+use crate::llm::provider::LLMProvider;
+use crate::llm::providers::{
+    anthropic::AnthropicProvider, cohere::CohereProvider, groq, minimax::MinimaxProvider, ollama,
+    openai, qwen, xai, zai,
+};
+
+/// Builds a provider from an API key and an optional model override
+pub type ProviderConstructor = fn(&str, Option<String>) -> Box<dyn LLMProvider>;
+
+/// Description of a built-in LLM provider
+pub struct ProviderDescriptor {
+    /// Key used by `/config`, `/use` and the config file
+    pub name: &'static str,
+    /// Other accepted spellings of the name
+    pub aliases: &'static [&'static str],
+    /// Name shown to users
+    pub display_name: &'static str,
+    /// Model used when none is configured
+    pub default_model: &'static str,
+    /// Well-known models offered by the TUI model picker, default first
+    pub models: &'static [&'static str],
+    /// Whether the provider needs an API key (local servers do not)
+    pub requires_api_key: bool,
+    /// Extra line shown by `/providers`
+    pub notes: Option<&'static str>,
+    /// Constructor for the provider client
+    pub constructor: ProviderConstructor,
+}
+
+impl ProviderDescriptor {
+    /// Check whether `name` refers to this provider
+    pub fn matches(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+            || self.aliases.iter().any(|alias| alias.eq_ignore_ascii_case(name))
+    }
+
+    /// Command that configures this provider
+    pub fn config_hint(&self) -> String {
+        if self.requires_api_key {
+            format!("/config {} <api-key>", self.name)
+        } else {
+            format!("/config {}", self.name)
+        }
+    }
+}
+
+static PROVIDERS: &[ProviderDescriptor] = &[
+    ProviderDescriptor {
+        name: "anthropic",
+        aliases: &[],
+        display_name: "Anthropic",
+        default_model: "claude-sonnet-4-20250514",
+        models: &["claude-sonnet-4-20250514", "claude-opus-4-20250514", "claude-3-5-haiku-latest"],
+        requires_api_key: true,
+        notes: None,
+        constructor: |api_key, model| Box::new(AnthropicProvider::new(api_key, model)),
+    },
+    ProviderDescriptor {
+        name: "openai",
+        aliases: &[],
+        display_name: "OpenAI",
+        default_model: "gpt-4o",
+        models: &["gpt-4o", "gpt-4o-mini", "gpt-4.1", "o4-mini"],
+        requires_api_key: true,
+        notes: None,
+        constructor: |api_key, model| Box::new(openai::provider(api_key, model)),
+    },
+    ProviderDescriptor {
+        name: "ollama",
+        aliases: &[],
+        display_name: "Ollama",
+        default_model: "llama3.2",
+        models: &["llama3.2", "qwen2.5-coder", "mistral"],
+        requires_api_key: false,
+        notes: Some("local server at http://localhost:11434"),
+        constructor: |api_key, model| Box::new(ollama::provider(api_key, model)),
+    },
+    ProviderDescriptor {
+        name: "groq",
+        aliases: &[],
+        display_name: "Groq",
+        default_model: "llama-3.3-70b-versatile",
+        models: &["llama-3.3-70b-versatile", "llama-3.1-8b-instant", "gemma2-9b-it"],
+        requires_api_key: true,
+        notes: None,
+        constructor: |api_key, model| Box::new(groq::provider(api_key, model)),
+    },
+    ProviderDescriptor {
+        name: "cohere",
+        aliases: &[],
+        display_name: "Cohere",
+        default_model: "command-r-plus",
+        models: &["command-r-plus", "command-r", "command-a-03-2025"],
+        requires_api_key: true,
+        notes: None,
+        constructor: |api_key, model| Box::new(CohereProvider::new(api_key, model)),
+    },
+    ProviderDescriptor {
+        name: "xai",
+        aliases: &[],
+        display_name: "xAI",
+        default_model: "grok-2",
+        models: &["grok-2", "grok-3", "grok-beta"],
+        requires_api_key: true,
+        notes: None,
+        constructor: |api_key, model| Box::new(xai::provider(api_key, model)),
+    },
+    ProviderDescriptor {
+        name: "minimax",
+        aliases: &[],
+        display_name: "Minimax",
+        default_model: "abab6.5s-chat",
+        models: &["abab6.5s-chat", "abab5.5-chat"],
+        requires_api_key: true,
+        notes: None,
+        constructor: |api_key, model| Box::new(MinimaxProvider::new(api_key, model)),
+    },
+    ProviderDescriptor {
+        name: "qwen",
+        aliases: &[],
+        display_name: "Qwen",
+        default_model: "qwen-max",
+        models: &["qwen-max", "qwen-plus", "qwen-turbo"],
+        requires_api_key: true,
+        notes: None,
+        constructor: |api_key, model| Box::new(qwen::provider(api_key, model)),
+    },
+    ProviderDescriptor {
+        name: "zai",
+        aliases: &["z.ai"],
+        display_name: "Z.ai",
+        default_model: "deepseek-r1",
+        models: &["deepseek-r1", "deepseek-v3", "glm-4"],
+        requires_api_key: true,
+        notes: None,
+        constructor: |api_key, model| Box::new(zai::provider(api_key, model)),
+    },
+];
+
+/// All registered providers, in display order
+pub fn providers() -> &'static [ProviderDescriptor] {
+    PROVIDERS
+}
+
+/// Look up a provider by name or alias, ignoring case
+pub fn find_provider(name: &str) -> Option<&'static ProviderDescriptor> {
+    PROVIDERS.iter().find(|descriptor| descriptor.matches(name))
+}
+
+/// Registry name for `name`, or `name` unchanged when it is not registered
+pub fn canonical_name(name: &str) -> String {
+    find_provider(name)
+        .map(|descriptor| descriptor.name.to_string())
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// Check whether `name` is a registered provider or alias
+pub fn is_known_provider(name: &str) -> bool {
+    find_provider(name).is_some()
+}
+
+/// Create a provider client by name
+///
+/// Without a model override the registry's default model is used, so the
+/// configured default wins over the client's own built-in fallback.
+pub fn create_provider(
+    name: &str,
+    api_key: &str,
+    model: Option<String>,
+) -> Result<Box<dyn LLMProvider>> {
+    let descriptor = find_provider(name).ok_or_else(|| {
+        SchemaForgeError::InvalidInput(format!(
+            "Unknown provider: '{}'. Supported: {}",
+            name,
+            provider_names().join(", ")
+        ))
+    })?;
+    let model = model.unwrap_or_else(|| descriptor.default_model.to_string());
+    Ok((descriptor.constructor)(api_key, Some(model)))
+}
+
+/// Endpoint override the provider reads from the environment, if any
+pub fn base_url_override(name: &str) -> Option<String> {
+    match find_provider(name)?.name {
+        "ollama" => std::env::var("OLLAMA_BASE_URL").ok(),
+        _ => None,
+    }
+}
+
+/// Canonical names of all registered providers
+pub fn provider_names() -> Vec<&'static str> {
+    PROVIDERS.iter().map(|descriptor| descriptor.name).collect()
+}
+
+/// Default model for each provider, keyed by canonical name
+pub fn default_models() -> std::collections::HashMap<String, String> {
+    PROVIDERS
+        .iter()
+        .map(|descriptor| (descriptor.name.to_string(), descriptor.default_model.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_provider_constructs() {
+        for descriptor in providers() {
+            let provider = create_provider(descriptor.name, "test-key", None).unwrap();
+            assert!(!provider.provider_name().is_empty(), "{}", descriptor.name);
+            assert!(!descriptor.default_model.is_empty());
+            assert_eq!(descriptor.models.first(), Some(&descriptor.default_model));
+        }
+    }
+
+    #[test]
+    fn test_find_provider_by_alias() {
+        assert_eq!(find_provider("Z.AI").map(|descriptor| descriptor.name), Some("zai"));
+        assert_eq!(find_provider("OpenAI").map(|descriptor| descriptor.name), Some("openai"));
+        assert!(find_provider("unknown").is_none());
+        assert!(is_known_provider("groq") && !is_known_provider("bard"));
+        assert_eq!(canonical_name("z.ai"), "zai");
+        assert_eq!(canonical_name("bard"), "bard");
+        assert!(create_provider("unknown", "key", None).is_err());
+    }
+
+    #[test]
+    fn test_configured_default_model_wins() {
+        let defaults = crate::config::storage::Config::default_models();
+        let provider = create_provider("anthropic", "test-key", None).unwrap();
+        assert_eq!(provider.model_name(), defaults["anthropic"]);
+        assert_ne!(
+            AnthropicProvider::new("test-key", None).model_name(),
+            defaults["anthropic"]
+        );
+
+        let provider =
+            create_provider("anthropic", "test-key", Some("claude-3-haiku".to_string())).unwrap();
+        assert_eq!(provider.model_name(), "claude-3-haiku");
+    }
+}
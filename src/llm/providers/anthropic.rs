@@ -4,14 +4,25 @@
 
 use crate::error::{Result, SchemaForgeError};
 use crate::llm::client::LLMHttpClient;
-use crate::llm::provider::{GenerationParams, LLMResponse, LLMProvider, Message, MessageRole};
+use crate::llm::provider::{
+    lenient_usage, lookup_context_window, split_system_messages, strip_sql_fences,
+    GenerationParams, LLMResponse, LLMProvider, Message, MessageRole,
+};
 use async_trait::async_trait;
 use reqwest::header::{HeaderMap, CONTENT_TYPE};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Anthropic API base URL
 const ANTHROPIC_API_BASE: &str = "https://api.anthropic.com/v1/messages";
 
+/// Context windows in tokens, keyed by model name prefix
+const CONTEXT_WINDOWS: &[(&str, u32)] = &[
+    ("claude-3", 200_000),
+    ("claude-sonnet-4", 200_000),
+    ("claude-opus-4", 200_000),
+];
+
 /// Anthropic Claude API provider
 pub struct AnthropicProvider {
     /// API key for authentication
@@ -20,6 +31,8 @@ pub struct AnthropicProvider {
     model: String,
     /// HTTP client for making requests
     client: LLMHttpClient,
+    /// Extra headers from `/config header`
+    custom_headers: HashMap<String, String>,
     /// API version
     version: String,
     /// Maximum tokens for generation
@@ -38,6 +51,7 @@ impl AnthropicProvider {
             api_key: api_key.into(),
             model,
             client: LLMHttpClient::new().expect("Failed to create HTTP client"),
+            custom_headers: HashMap::new(),
             version: "2023-06-01".to_string(),
             max_tokens: 4096,
         }
@@ -56,28 +70,35 @@ impl AnthropicProvider {
     }
 
     /// Build headers for Anthropic API
-    fn build_headers(&self) -> HeaderMap {
+    fn build_headers(&self) -> Result<HeaderMap> {
         let mut headers = HeaderMap::new();
         headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
         headers.insert("x-api-key", self.api_key.parse().unwrap());
         headers.insert("anthropic-version", self.version.parse().unwrap());
-        headers
+        LLMHttpClient::add_headers(headers, &self.custom_headers)
     }
 
     /// Convert our Message format to Anthropic format
-    fn convert_messages_to_anthropic(&self, messages: &[Message]) -> Vec<AnthropicMessage> {
-        messages
-            .iter()
+    ///
+    /// System messages go in the request's `system` field instead, so the
+    /// joined system text is returned separately.
+    fn convert_messages_to_anthropic(
+        &self,
+        messages: &[Message],
+    ) -> (Option<String>, Vec<AnthropicMessage>) {
+        let (system, messages) = split_system_messages(messages);
+        let messages = messages
+            .into_iter()
             .map(|msg| AnthropicMessage {
                 role: match msg.role {
-                    MessageRole::User => "user",
                     MessageRole::Assistant => "assistant",
-                    MessageRole::System => "user", // Anthropic doesn't have system role in messages
+                    _ => "user",
                 }
                 .to_string(),
-                content: msg.content.clone(),
+                content: msg.content,
             })
-            .collect()
+            .collect();
+        (system, messages)
     }
 
     /// Extract text content from Anthropic response
@@ -115,7 +136,7 @@ impl LLMProvider for AnthropicProvider {
 
         let temperature: f64 = params.and_then(|p| p.temperature).unwrap_or(0.7) as f64;
 
-        let anthropic_messages = self.convert_messages_to_anthropic(messages);
+        let (system, anthropic_messages) = self.convert_messages_to_anthropic(messages);
 
         let request = AnthropicRequest {
             model: self.model.clone(),
@@ -126,11 +147,11 @@ impl LLMProvider for AnthropicProvider {
             stop_sequences: params
                 .and_then(|p| p.stop_sequences.clone())
                 .unwrap_or_default(),
-            system: None, // System messages are handled in the messages array
+            system,
             stream: false,
         };
 
-        let headers = self.build_headers();
+        let headers = self.build_headers()?;
         let response_text = self
             .client
             .post_with_retry(ANTHROPIC_API_BASE, headers, &request)
@@ -150,9 +171,9 @@ impl LLMProvider for AnthropicProvider {
         Ok(LLMResponse {
             content,
             model: Some(anthropic_response.model),
-            input_tokens: Some(anthropic_response.usage.input_tokens),
-            output_tokens: Some(anthropic_response.usage.output_tokens),
-            total_tokens: Some(anthropic_response.usage.input_tokens + anthropic_response.usage.output_tokens),
+            input_tokens: anthropic_response.usage.as_ref().map(|u| u.input_tokens),
+            output_tokens: anthropic_response.usage.as_ref().map(|u| u.output_tokens),
+            total_tokens: anthropic_response.usage.as_ref().map(|u| u.input_tokens + u.output_tokens),
             finish_reason: anthropic_response.stop_reason,
         })
     }
@@ -188,7 +209,7 @@ impl LLMProvider for AnthropicProvider {
             stream: false,
         };
 
-        let headers = self.build_headers();
+        let headers = self.build_headers()?;
         let response_text = self
             .client
             .post_with_retry(ANTHROPIC_API_BASE, headers, &request)
@@ -208,9 +229,9 @@ impl LLMProvider for AnthropicProvider {
         Ok(LLMResponse {
             content,
             model: Some(anthropic_response.model),
-            input_tokens: Some(anthropic_response.usage.input_tokens),
-            output_tokens: Some(anthropic_response.usage.output_tokens),
-            total_tokens: Some(anthropic_response.usage.input_tokens + anthropic_response.usage.output_tokens),
+            input_tokens: anthropic_response.usage.as_ref().map(|u| u.input_tokens),
+            output_tokens: anthropic_response.usage.as_ref().map(|u| u.output_tokens),
+            total_tokens: anthropic_response.usage.as_ref().map(|u| u.input_tokens + u.output_tokens),
             finish_reason: anthropic_response.stop_reason,
         })
     }
@@ -253,7 +274,7 @@ Return only the SQL query with no markdown formatting.";
             stream: false,
         };
 
-        let headers = self.build_headers();
+        let headers = self.build_headers()?;
         let response_text = self
             .client
             .post_with_retry(ANTHROPIC_API_BASE, headers, &request)
@@ -269,7 +290,17 @@ Return only the SQL query with no markdown formatting.";
             })?;
 
         let content = self.extract_content(&anthropic_response);
-        Ok(content.trim().to_string())
+        Ok(strip_sql_fences(&content))
+    }
+
+    /// Get the configured model
+    fn model_name(&self) -> &str {
+        &self.model
+    }
+
+    /// Context window of the configured model, if known
+    fn max_context_tokens(&self) -> Option<u32> {
+        lookup_context_window(CONTEXT_WINDOWS, &self.model)
     }
 
     /// Get provider name
@@ -281,6 +312,14 @@ Return only the SQL query with no markdown formatting.";
     fn has_api_key(&self) -> bool {
         !self.api_key.is_empty()
     }
+
+    fn set_custom_headers(&mut self, headers: HashMap<String, String>) {
+        self.custom_headers = headers;
+    }
+
+    fn set_max_retries(&mut self, max_retries: u32) {
+        self.client = self.client.clone().with_max_retries(max_retries);
+    }
 }
 
 /// Anthropic API request format
@@ -315,7 +354,8 @@ struct AnthropicResponse {
     content: Vec<ContentBlock>,
     model: String,
     stop_reason: Option<String>,
-    usage: Usage,
+    #[serde(default, deserialize_with = "lenient_usage")]
+    usage: Option<Usage>,
 }
 
 /// Content block in Anthropic response
@@ -350,6 +390,16 @@ mod tests {
         assert_eq!(provider.model, "claude-3-opus");
     }
 
+    #[test]
+    fn test_anthropic_context_window() {
+        let provider = AnthropicProvider::new("test-key", Some("claude-sonnet-4-20250514".to_string()));
+        assert_eq!(provider.model_name(), "claude-sonnet-4-20250514");
+        assert_eq!(provider.max_context_tokens(), Some(200_000));
+
+        let provider = AnthropicProvider::new("test-key", Some("claude-instant".to_string()));
+        assert_eq!(provider.max_context_tokens(), None);
+    }
+
     #[test]
     fn test_anthropic_provider_with_max_tokens() {
         let provider = AnthropicProvider::new("test-key", None).with_max_tokens(8192);
@@ -378,7 +428,8 @@ mod tests {
             },
         ];
 
-        let anthropic_messages = provider.convert_messages_to_anthropic(&messages);
+        let (system, anthropic_messages) = provider.convert_messages_to_anthropic(&messages);
+        assert_eq!(system, None);
         assert_eq!(anthropic_messages.len(), 2);
         assert_eq!(anthropic_messages[0].role, "user");
         assert_eq!(anthropic_messages[0].content, "Hello");
@@ -386,6 +437,18 @@ mod tests {
         assert_eq!(anthropic_messages[1].content, "Hi there!");
     }
 
+    #[test]
+    fn test_system_messages_use_system_field() {
+        let provider = AnthropicProvider::new("test-key", None);
+        assert!(provider.supports_system_role());
+
+        let messages = vec![Message::system("Use SQLite."), Message::user("Count users")];
+        let (system, anthropic_messages) = provider.convert_messages_to_anthropic(&messages);
+        assert_eq!(system.as_deref(), Some("Use SQLite."));
+        assert_eq!(anthropic_messages.len(), 1);
+        assert_eq!(anthropic_messages[0].content, "Count users");
+    }
+
     #[test]
     fn test_has_api_key() {
         let provider = AnthropicProvider::new("test-key", None);
@@ -394,4 +457,25 @@ mod tests {
         let provider = AnthropicProvider::new("", None);
         assert!(!provider.has_api_key());
     }
+
+    #[test]
+    fn test_response_without_usage_still_parses() {
+        let provider = AnthropicProvider::new("test-key", None);
+        let body = r#"{"id":"msg_1","role":"assistant","model":"claude-3-5-sonnet-20241022",
+            "content":[{"type":"text","text":"SELECT 1"}],"stop_reason":"end_turn"}"#;
+        let response: AnthropicResponse = serde_json::from_str(body).unwrap();
+        assert!(response.usage.is_none());
+        assert_eq!(provider.extract_content(&response), "SELECT 1");
+
+        let odd = body.replace("\"stop_reason\"", "\"usage\":{\"cache_read\":true},\"stop_reason\"");
+        let response: AnthropicResponse = serde_json::from_str(&odd).unwrap();
+        assert!(response.usage.is_none());
+
+        let full = body.replace(
+            "\"stop_reason\"",
+            "\"usage\":{\"input_tokens\":10,\"output_tokens\":3},\"stop_reason\"",
+        );
+        let response: AnthropicResponse = serde_json::from_str(&full).unwrap();
+        assert_eq!(response.usage.map(|u| u.input_tokens + u.output_tokens), Some(13));
+    }
 }
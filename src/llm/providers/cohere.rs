@@ -4,13 +4,24 @@
 
 use crate::error::{Result, SchemaForgeError};
 use crate::llm::client::LLMHttpClient;
-use crate::llm::provider::{GenerationParams, LLMResponse, LLMProvider, Message, MessageRole};
+use crate::llm::provider::{
+    lenient_usage, lookup_context_window, prepare_messages, strip_sql_fences, GenerationParams,
+    LLMResponse, LLMProvider, Message, MessageRole,
+};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Cohere API base URL
 const COHERE_API_BASE: &str = "https://api.cohere.ai/v1/chat";
 
+/// Context windows in tokens, keyed by model name prefix
+const CONTEXT_WINDOWS: &[(&str, u32)] = &[
+    ("command-r", 128_000),
+    ("command-r-plus", 128_000),
+    ("command-a", 256_000),
+];
+
 /// Cohere API provider
 pub struct CohereProvider {
     /// API key for authentication
@@ -19,6 +30,8 @@ pub struct CohereProvider {
     model: String,
     /// HTTP client for making requests
     client: LLMHttpClient,
+    /// Extra headers from `/config header`
+    custom_headers: HashMap<String, String>,
 }
 
 impl CohereProvider {
@@ -33,27 +46,16 @@ impl CohereProvider {
             api_key: api_key.into(),
             model,
             client: LLMHttpClient::new().expect("Failed to create HTTP client"),
+            custom_headers: HashMap::new(),
         }
     }
 
     /// Build headers for Cohere API
-    fn build_headers(&self) -> reqwest::header::HeaderMap {
-        LLMHttpClient::build_headers_with_auth("Authorization", &format!("Bearer {}", self.api_key))
-    }
-
-    /// Convert our Message format to Cohere format
-    fn convert_messages_to_cohere(&self, messages: &[Message]) -> String {
-        // Cohere uses a single chat_history with role/message format
-        // Build a conversation string
-        messages
-            .iter()
-            .map(|msg| match msg.role {
-                MessageRole::User => format!("User: {}", msg.content),
-                MessageRole::Assistant => format!("Chatbot: {}", msg.content),
-                MessageRole::System => format!("System: {}", msg.content),
-            })
-            .collect::<Vec<_>>()
-            .join("\n\n")
+    fn build_headers(&self) -> Result<reqwest::header::HeaderMap> {
+        LLMHttpClient::add_headers(
+            LLMHttpClient::build_headers_with_auth("Authorization", &format!("Bearer {}", self.api_key)),
+            &self.custom_headers,
+        )
     }
 }
 
@@ -65,26 +67,23 @@ impl LLMProvider for CohereProvider {
         messages: &[Message],
         params: Option<&GenerationParams>,
     ) -> Result<LLMResponse> {
-        // Get the last message as the main query
-        let last_message = messages
-            .last()
-            .map(|m| m.content.clone())
-            .unwrap_or_default();
+        // Cohere has no system role, so system prompts ride on the first user message
+        let messages = prepare_messages(self, messages);
+        let (last_message, history) = match messages.split_last() {
+            Some((last, history)) => (last.content.clone(), history),
+            None => (String::new(), &[][..]),
+        };
 
         // Build chat_history from previous messages
-        let chat_history: Vec<CohereChatMessage> = messages
+        let chat_history: Vec<CohereChatMessage> = history
             .iter()
-            .take(messages.len() - 1)
-            .filter_map(|msg| {
-                let role = match msg.role {
-                    MessageRole::User => "USER",
+            .map(|msg| CohereChatMessage {
+                role: match msg.role {
                     MessageRole::Assistant => "CHATBOT",
-                    MessageRole::System => return None, // Skip system messages in history
-                };
-                Some(CohereChatMessage {
-                    role: role.to_string(),
-                    message: msg.content.clone(),
-                })
+                    _ => "USER",
+                }
+                .to_string(),
+                message: msg.content.clone(),
             })
             .collect();
 
@@ -100,7 +99,7 @@ impl LLMProvider for CohereProvider {
             stop_sequences: params.and_then(|p| p.stop_sequences.clone()),
         };
 
-        let headers = self.build_headers();
+        let headers = self.build_headers()?;
         let response_text = self
             .client
             .post_with_retry(COHERE_API_BASE, headers, &request)
@@ -115,14 +114,13 @@ impl LLMProvider for CohereProvider {
                 }
             })?;
 
+        let tokens = cohere_response.meta.and_then(|meta| meta.tokens);
         Ok(LLMResponse {
             content: cohere_response.text,
             model: Some(cohere_response.response_id), // Cohere doesn't return model name
-            input_tokens: Some(cohere_response.meta.tokens.input_tokens),
-            output_tokens: Some(cohere_response.meta.tokens.output_tokens),
-            total_tokens: Some(
-                cohere_response.meta.tokens.input_tokens + cohere_response.meta.tokens.output_tokens,
-            ),
+            input_tokens: tokens.as_ref().map(|t| t.input_tokens),
+            output_tokens: tokens.as_ref().map(|t| t.output_tokens),
+            total_tokens: tokens.as_ref().map(|t| t.input_tokens + t.output_tokens),
             finish_reason: cohere_response.finish_reason,
         })
     }
@@ -136,48 +134,11 @@ impl LLMProvider for CohereProvider {
     ) -> Result<LLMResponse> {
         let preamble = "You are a database expert. Answer questions about database schemas based on the provided context.";
 
-        let chat_history = vec![CohereChatMessage {
-            role: "SYSTEM".to_string(),
-            message: format!("{}\n\nDatabase Schema:\n{}", preamble, schema_context),
-        }];
-
-        let temperature: f32 = params.and_then(|p| p.temperature).unwrap_or(0.7);
-
-        let request = CohereRequest {
-            message: user_query.to_string(),
-            chat_history: Some(chat_history),
-            model: self.model.clone(),
-            temperature: Some(temperature),
-            max_tokens: params.and_then(|p| p.max_tokens),
-            p: params.and_then(|p| p.top_p),
-            stop_sequences: params.and_then(|p| p.stop_sequences.clone()),
-        };
-
-        let headers = self.build_headers();
-        let response_text = self
-            .client
-            .post_with_retry(COHERE_API_BASE, headers, &request)
-            .await?;
-
-        let cohere_response: CohereResponse =
-            serde_json::from_str(&response_text).map_err(|e| {
-                SchemaForgeError::LLMApiError {
-                    provider: "Cohere".to_string(),
-                    message: format!("Failed to parse response: {}", e),
-                    status: 0,
-                }
-            })?;
-
-        Ok(LLMResponse {
-            content: cohere_response.text,
-            model: Some(cohere_response.response_id),
-            input_tokens: Some(cohere_response.meta.tokens.input_tokens),
-            output_tokens: Some(cohere_response.meta.tokens.output_tokens),
-            total_tokens: Some(
-                cohere_response.meta.tokens.input_tokens + cohere_response.meta.tokens.output_tokens,
-            ),
-            finish_reason: cohere_response.finish_reason,
-        })
+        let messages = vec![
+            Message::system(format!("{}\n\nDatabase Schema:\n{}", preamble, schema_context)),
+            Message::user(user_query),
+        ];
+        self.generate(&messages, params).await
     }
 
     /// Generate SQL from natural language
@@ -198,37 +159,25 @@ Rules:
 
 Return only the SQL query with no markdown formatting.";
 
-        let chat_history = vec![CohereChatMessage {
-            role: "SYSTEM".to_string(),
-            message: format!("{}\n\nDatabase Schema:\n{}", preamble, schema_context),
-        }];
-
-        let request = CohereRequest {
-            message: natural_language_query.to_string(),
-            chat_history: Some(chat_history),
-            model: self.model.clone(),
-            temperature: Some(0.3), // Lower temperature for SQL
-            max_tokens: Some(2048),
-            p: None,
-            stop_sequences: None,
-        };
+        let messages = vec![
+            Message::system(format!("{}\n\nDatabase Schema:\n{}", preamble, schema_context)),
+            Message::user(natural_language_query),
+        ];
+        // Lower temperature for SQL
+        let params = GenerationParams::new().with_temperature(0.3).with_max_tokens(2048);
+        let response = self.generate(&messages, Some(&params)).await?;
 
-        let headers = self.build_headers();
-        let response_text = self
-            .client
-            .post_with_retry(COHERE_API_BASE, headers, &request)
-            .await?;
+        Ok(strip_sql_fences(&response.content))
+    }
 
-        let cohere_response: CohereResponse =
-            serde_json::from_str(&response_text).map_err(|e| {
-                SchemaForgeError::LLMApiError {
-                    provider: "Cohere".to_string(),
-                    message: format!("Failed to parse response: {}", e),
-                    status: 0,
-                }
-            })?;
+    /// Get the configured model
+    fn model_name(&self) -> &str {
+        &self.model
+    }
 
-        Ok(cohere_response.text.trim().to_string())
+    /// Context window of the configured model, if known
+    fn max_context_tokens(&self) -> Option<u32> {
+        lookup_context_window(CONTEXT_WINDOWS, &self.model)
     }
 
     /// Get provider name
@@ -240,6 +189,19 @@ Return only the SQL query with no markdown formatting.";
     fn has_api_key(&self) -> bool {
         !self.api_key.is_empty()
     }
+
+    fn set_custom_headers(&mut self, headers: HashMap<String, String>) {
+        self.custom_headers = headers;
+    }
+
+    fn set_max_retries(&mut self, max_retries: u32) {
+        self.client = self.client.clone().with_max_retries(max_retries);
+    }
+
+    /// Cohere's chat API has no system role
+    fn supports_system_role(&self) -> bool {
+        false
+    }
 }
 
 /// Cohere API request format
@@ -273,13 +235,15 @@ struct CohereResponse {
     text: String,
     response_id: String,
     finish_reason: Option<String>,
-    meta: CohereMeta,
+    #[serde(default, deserialize_with = "lenient_usage")]
+    meta: Option<CohereMeta>,
 }
 
 /// Cohere metadata
 #[derive(Debug, Deserialize, Clone)]
 struct CohereMeta {
-    tokens: CohereTokens,
+    #[serde(default, deserialize_with = "lenient_usage")]
+    tokens: Option<CohereTokens>,
 }
 
 /// Cohere token usage
@@ -313,4 +277,21 @@ mod tests {
         let provider = CohereProvider::new("", None);
         assert!(!provider.has_api_key());
     }
+
+    #[test]
+    fn test_response_without_usage_still_parses() {
+        let body = r#"{"text":"SELECT 1","response_id":"r1","finish_reason":"COMPLETE"}"#;
+        let response: CohereResponse = serde_json::from_str(body).unwrap();
+        assert_eq!(response.text, "SELECT 1");
+        assert!(response.meta.is_none());
+
+        let body = r#"{"text":"SELECT 1","response_id":"r1","meta":{"api_version":{"version":"1"}}}"#;
+        let response: CohereResponse = serde_json::from_str(body).unwrap();
+        assert!(response.meta.unwrap().tokens.is_none());
+
+        let body = r#"{"text":"SELECT 1","response_id":"r1","meta":{"tokens":{"input_tokens":7,"output_tokens":2}}}"#;
+        let response: CohereResponse = serde_json::from_str(body).unwrap();
+        let tokens = response.meta.and_then(|meta| meta.tokens).unwrap();
+        assert_eq!((tokens.input_tokens, tokens.output_tokens), (7, 2));
+    }
 }
@@ -4,13 +4,23 @@
 
 use crate::error::{Result, SchemaForgeError};
 use crate::llm::client::LLMHttpClient;
-use crate::llm::provider::{GenerationParams, LLMResponse, LLMProvider, Message, MessageRole};
+use crate::llm::provider::{
+    lenient_usage, lookup_context_window, strip_sql_fences, GenerationParams, LLMResponse,
+    LLMProvider, Message, MessageRole,
+};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Minimax API base URL
 const MINIMAX_API_BASE: &str = "https://api.minimax.chat/v1/text/chatcompletion_v2";
 
+/// Context windows in tokens, keyed by model name prefix
+const CONTEXT_WINDOWS: &[(&str, u32)] = &[
+    ("abab5.5", 16_384),
+    ("abab6.5s", 245_760),
+];
+
 /// Minimax API provider
 pub struct MinimaxProvider {
     /// API key for authentication
@@ -19,6 +29,8 @@ pub struct MinimaxProvider {
     model: String,
     /// HTTP client for making requests
     client: LLMHttpClient,
+    /// Extra headers from `/config header`
+    custom_headers: HashMap<String, String>,
 }
 
 impl MinimaxProvider {
@@ -33,12 +45,13 @@ impl MinimaxProvider {
             api_key: api_key.into(),
             model,
             client: LLMHttpClient::new().expect("Failed to create HTTP client"),
+            custom_headers: HashMap::new(),
         }
     }
 
     /// Build headers for Minimax API
-    fn build_headers(&self) -> reqwest::header::HeaderMap {
-        LLMHttpClient::build_headers(&self.api_key)
+    fn build_headers(&self) -> Result<reqwest::header::HeaderMap> {
+        LLMHttpClient::add_headers(LLMHttpClient::build_headers(&self.api_key), &self.custom_headers)
     }
 
     /// Convert our Message format to Minimax format
@@ -79,7 +92,7 @@ impl LLMProvider for MinimaxProvider {
             max_tokens: params.and_then(|p| p.max_tokens),
         };
 
-        let headers = self.build_headers();
+        let headers = self.build_headers()?;
         let response_text = self
             .client
             .post_with_retry(MINIMAX_API_BASE, headers, &request)
@@ -103,11 +116,9 @@ impl LLMProvider for MinimaxProvider {
         Ok(LLMResponse {
             content,
             model: Some(self.model.clone()),
-            input_tokens: Some(minimax_response.usage.input_tokens),
-            output_tokens: Some(minimax_response.usage.output_tokens),
-            total_tokens: Some(
-                minimax_response.usage.input_tokens + minimax_response.usage.output_tokens,
-            ),
+            input_tokens: minimax_response.usage.as_ref().map(|u| u.input_tokens),
+            output_tokens: minimax_response.usage.as_ref().map(|u| u.output_tokens),
+            total_tokens: minimax_response.usage.as_ref().map(|u| u.input_tokens + u.output_tokens),
             finish_reason: None,
         })
     }
@@ -171,7 +182,17 @@ Return only the SQL query with no markdown formatting.";
         ];
 
         let response = self.generate(&messages, None).await?;
-        Ok(response.content.trim().to_string())
+        Ok(strip_sql_fences(&response.content))
+    }
+
+    /// Get the configured model
+    fn model_name(&self) -> &str {
+        &self.model
+    }
+
+    /// Context window of the configured model, if known
+    fn max_context_tokens(&self) -> Option<u32> {
+        lookup_context_window(CONTEXT_WINDOWS, &self.model)
     }
 
     /// Get provider name
@@ -183,6 +204,14 @@ Return only the SQL query with no markdown formatting.";
     fn has_api_key(&self) -> bool {
         !self.api_key.is_empty()
     }
+
+    fn set_custom_headers(&mut self, headers: HashMap<String, String>) {
+        self.custom_headers = headers;
+    }
+
+    fn set_max_retries(&mut self, max_retries: u32) {
+        self.client = self.client.clone().with_max_retries(max_retries);
+    }
 }
 
 /// Minimax API request format
@@ -212,7 +241,8 @@ struct MinimaxMessage {
 struct MinimaxResponse {
     base_resp: MinimaxBaseResp,
     choices: Vec<MinimaxChoice>,
-    usage: MinimaxUsage,
+    #[serde(default, deserialize_with = "lenient_usage")]
+    usage: Option<MinimaxUsage>,
 }
 
 /// Minimax base response
@@ -0,0 +1,468 @@
+//! OpenAI-compatible chat completions
+//!
+//! OpenAI, Groq, xAI, Qwen, Z.ai and Ollama all speak the OpenAI chat
+//! completions format. Each is described by an [`OpenAICompatibleEndpoint`]
+//! and served by the one [`OpenAICompatibleProvider`] implementation.
+
+use crate::error::{Result, SchemaForgeError};
+use crate::llm::client::LLMHttpClient;
+use crate::llm::provider::{
+    combine_choice_contents, lenient_usage, lookup_context_window, strip_sql_fences,
+    GenerationParams, LLMResponse, LLMProvider, Message, MessageRole,
+};
+use async_trait::async_trait;
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How the API key is sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthScheme {
+    /// `Authorization: Bearer <key>`
+    Bearer,
+    /// The bare key in the named header, such as Azure's `api-key`
+    Header(&'static str),
+}
+
+/// Description of one OpenAI-compatible service
+#[derive(Debug)]
+pub struct OpenAICompatibleEndpoint {
+    /// Name shown to users and in errors
+    pub name: &'static str,
+    /// Full chat completions URL
+    pub base_url: &'static str,
+    /// Model used when none is configured
+    pub default_model: &'static str,
+    /// How the API key is sent
+    pub auth_scheme: AuthScheme,
+    /// Context windows in tokens, keyed by model name prefix
+    pub context_windows: &'static [(&'static str, u32)],
+    /// Temperature used when the caller does not set one
+    pub default_temperature: f32,
+}
+
+/// System prompt for [`LLMProvider::generate_sql`]
+const SQL_SYSTEM_PROMPT: &str = "You are a SQL expert. Convert natural language queries to SQL based on the provided database schema.
+
+Rules:
+1. Return ONLY the SQL query, no explanations
+2. Use proper table and column names from the schema
+3. Handle NULL values appropriately
+4. Use proper JOIN syntax
+5. Add appropriate WHERE clauses
+6. Format SQL in a readable way
+7. For PostgreSQL, use ::text for type casting
+8. For MySQL, use CAST for type casting
+9. For SQLite, use CAST for type casting
+10. For MSSQL, use CAST for type casting
+
+Return only the SQL query with no markdown formatting.";
+
+/// Provider for any OpenAI-compatible endpoint
+pub struct OpenAICompatibleProvider {
+    /// Service this provider talks to
+    endpoint: &'static OpenAICompatibleEndpoint,
+    /// API key for authentication
+    api_key: String,
+    /// Model to use
+    model: String,
+    /// Chat completions URL, the endpoint's unless overridden
+    url: String,
+    /// HTTP client for making requests
+    client: LLMHttpClient,
+    /// Extra headers from `/config header`
+    custom_headers: HashMap<String, String>,
+    /// Maximum tokens for generation
+    max_tokens: u32,
+}
+
+impl OpenAICompatibleProvider {
+    /// Create a provider for `endpoint`, using its default model unless `model` is given
+    pub fn new(
+        endpoint: &'static OpenAICompatibleEndpoint,
+        api_key: impl Into<String>,
+        model: Option<String>,
+    ) -> Self {
+        Self {
+            endpoint,
+            api_key: api_key.into(),
+            model: model.unwrap_or_else(|| endpoint.default_model.to_string()),
+            url: endpoint.base_url.to_string(),
+            client: LLMHttpClient::new().expect("Failed to create HTTP client"),
+            custom_headers: HashMap::new(),
+            max_tokens: 4096,
+        }
+    }
+
+    /// Set the maximum tokens for generation
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// Send requests to `url` instead of the endpoint's own URL
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// Chat completions URL requests are sent to
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Request headers, carrying the API key the way the endpoint expects
+    pub fn build_headers(&self) -> Result<HeaderMap> {
+        let headers = match self.endpoint.auth_scheme {
+            AuthScheme::Bearer => LLMHttpClient::build_headers(&self.api_key),
+            AuthScheme::Header(name) => LLMHttpClient::build_headers_with_auth(name, &self.api_key),
+        };
+        LLMHttpClient::add_headers(headers, &self.custom_headers)
+    }
+
+    /// Convert our Message format to the OpenAI format
+    fn convert_messages(&self, messages: &[Message]) -> Vec<ChatMessage> {
+        messages
+            .iter()
+            .map(|msg| ChatMessage {
+                role: match msg.role {
+                    MessageRole::User => "user",
+                    MessageRole::Assistant => "assistant",
+                    MessageRole::System => "system",
+                }
+                .to_string(),
+                content: msg.content.clone(),
+            })
+            .collect()
+    }
+
+    /// Extract text content from a chat completions response
+    fn extract_content(&self, response: &ChatResponse) -> Result<String> {
+        combine_choice_contents(
+            self.provider_name(),
+            response
+                .choices
+                .iter()
+                .map(|choice| choice.message.content.as_deref()),
+        )
+    }
+}
+
+#[async_trait]
+impl LLMProvider for OpenAICompatibleProvider {
+    /// Generate a response from the chat completions endpoint
+    async fn generate(
+        &self,
+        messages: &[Message],
+        params: Option<&GenerationParams>,
+    ) -> Result<LLMResponse> {
+        let request = ChatRequest {
+            model: self.model.clone(),
+            messages: self.convert_messages(messages),
+            max_tokens: Some(params.and_then(|p| p.max_tokens).unwrap_or(self.max_tokens)),
+            temperature: Some(
+                params
+                    .and_then(|p| p.temperature)
+                    .unwrap_or(self.endpoint.default_temperature),
+            ),
+            top_p: params.and_then(|p| p.top_p),
+            stop: params.and_then(|p| p.stop_sequences.clone()),
+            stream: false,
+        };
+
+        let response_text = self
+            .client
+            .post_with_retry(&self.url, self.build_headers()?, &request)
+            .await?;
+
+        let response: ChatResponse = serde_json::from_str(&response_text).map_err(|e| {
+            SchemaForgeError::LLMApiError {
+                provider: self.endpoint.name.to_string(),
+                message: format!("Failed to parse response: {}", e),
+                status: 0,
+            }
+        })?;
+
+        Ok(LLMResponse {
+            content: self.extract_content(&response)?,
+            model: Some(response.model),
+            input_tokens: response.usage.as_ref().map(|u| u.prompt_tokens),
+            output_tokens: response.usage.as_ref().map(|u| u.completion_tokens),
+            total_tokens: response.usage.as_ref().map(|u| u.total_tokens),
+            finish_reason: response.choices.first().and_then(|c| c.finish_reason.clone()),
+        })
+    }
+
+    /// Generate a response with schema context
+    async fn generate_with_schema(
+        &self,
+        schema_context: &str,
+        user_query: &str,
+        params: Option<&GenerationParams>,
+    ) -> Result<LLMResponse> {
+        let messages = vec![
+            Message::system(format!(
+                "You are a database expert. Answer questions about database schemas based on the provided context.\n\nDatabase Schema:\n{}",
+                schema_context
+            )),
+            Message::user(user_query),
+        ];
+
+        self.generate(&messages, params).await
+    }
+
+    /// Generate SQL from natural language
+    async fn generate_sql(
+        &self,
+        schema_context: &str,
+        natural_language_query: &str,
+    ) -> Result<String> {
+        let messages = vec![
+            Message::system(SQL_SYSTEM_PROMPT),
+            Message::user(format!(
+                "Database Schema:\n{}\n\nQuery: {}",
+                schema_context, natural_language_query
+            )),
+        ];
+
+        let response = self.generate(&messages, None).await?;
+        Ok(strip_sql_fences(&response.content))
+    }
+
+    /// Get the configured model
+    fn model_name(&self) -> &str {
+        &self.model
+    }
+
+    /// Context window of the configured model, if known
+    fn max_context_tokens(&self) -> Option<u32> {
+        lookup_context_window(self.endpoint.context_windows, &self.model)
+    }
+
+    /// Get provider name
+    fn provider_name(&self) -> &str {
+        self.endpoint.name
+    }
+
+    /// Check if API key is set
+    fn has_api_key(&self) -> bool {
+        !self.api_key.is_empty()
+    }
+
+    fn set_custom_headers(&mut self, headers: HashMap<String, String>) {
+        self.custom_headers = headers;
+    }
+
+    fn set_max_retries(&mut self, max_retries: u32) {
+        self.client = self.client.clone().with_max_retries(max_retries);
+    }
+}
+
+/// Chat completions request
+#[derive(Debug, Serialize)]
+struct ChatRequest {
+    model: String,
+    messages: Vec<ChatMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop: Option<Vec<String>>,
+    stream: bool,
+}
+
+/// Chat message in a request
+#[derive(Debug, Serialize, Clone)]
+struct ChatMessage {
+    role: String,
+    content: String,
+}
+
+/// Chat completions response
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    model: String,
+    choices: Vec<Choice>,
+    #[serde(default, deserialize_with = "lenient_usage")]
+    usage: Option<Usage>,
+}
+
+/// Choice in a response
+#[derive(Debug, Deserialize, Clone)]
+struct Choice {
+    message: ChoiceMessage,
+    finish_reason: Option<String>,
+}
+
+/// Message in a choice
+#[derive(Debug, Deserialize, Clone)]
+struct ChoiceMessage {
+    content: Option<String>,
+}
+
+/// Token usage information
+#[derive(Debug, Deserialize, Clone)]
+struct Usage {
+    prompt_tokens: u32,
+    completion_tokens: u32,
+    total_tokens: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::providers::groq::GROQ;
+    use reqwest::header::AUTHORIZATION;
+
+    static AZURE_LIKE: OpenAICompatibleEndpoint = OpenAICompatibleEndpoint {
+        name: "Azure",
+        base_url: "https://example.openai.azure.com/openai/deployments/sql/chat/completions",
+        default_model: "gpt-4o",
+        auth_scheme: AuthScheme::Header("api-key"),
+        context_windows: &[("gpt-4o", 128_000)],
+        default_temperature: 0.7,
+    };
+
+    #[test]
+    fn test_endpoints_set_url_and_auth() {
+        let groq = OpenAICompatibleProvider::new(&GROQ, "groq-key", None);
+        assert_eq!(groq.url(), "https://api.groq.com/openai/v1/chat/completions");
+        assert_eq!(groq.provider_name(), "Groq");
+        assert_eq!(groq.model_name(), GROQ.default_model);
+        let headers = groq.build_headers().unwrap();
+        assert_eq!(headers[AUTHORIZATION], "Bearer groq-key");
+        assert!(headers.get("api-key").is_none());
+
+        let azure = OpenAICompatibleProvider::new(&AZURE_LIKE, "azure-key", Some("gpt-4o-mini".to_string()));
+        assert_eq!(azure.url(), AZURE_LIKE.base_url);
+        assert_eq!(azure.provider_name(), "Azure");
+        assert_eq!(azure.model_name(), "gpt-4o-mini");
+        assert_eq!(azure.max_context_tokens(), Some(128_000));
+        let headers = azure.build_headers().unwrap();
+        assert_eq!(headers["api-key"], "azure-key");
+        assert!(headers.get(AUTHORIZATION).is_none());
+
+        let local = OpenAICompatibleProvider::new(&AZURE_LIKE, "k", None).with_url("http://localhost:8080/v1/chat/completions");
+        assert_eq!(local.url(), "http://localhost:8080/v1/chat/completions");
+    }
+
+    #[test]
+    fn test_custom_headers_are_added_to_auth() {
+        let mut provider = OpenAICompatibleProvider::new(&GROQ, "groq-key", None);
+        provider.set_custom_headers(HashMap::from([(
+            "OpenAI-Organization".to_string(),
+            "org-123".to_string(),
+        )]));
+        let headers = provider.build_headers().unwrap();
+        assert_eq!(headers["openai-organization"], "org-123");
+        assert_eq!(headers[AUTHORIZATION], "Bearer groq-key");
+
+        provider.set_custom_headers(HashMap::from([("bad header".to_string(), "x".to_string())]));
+        assert!(provider.build_headers().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_zero_retries_sends_one_request_on_503() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/v1/chat/completions", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let mut buffer = [0u8; 8192];
+                let _ = socket.read(&mut buffer).await;
+                let _ = socket
+                    .write_all(b"HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+                    .await;
+            }
+        });
+
+        let mut provider = OpenAICompatibleProvider::new(&GROQ, "test-key", None).with_url(url);
+        provider.set_max_retries(0);
+        let error = provider.generate(&[Message::user("hi")], None).await.unwrap_err();
+        assert!(matches!(error, SchemaForgeError::LLMApiError { status: 503, .. }), "{:?}", error);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_provider_with_max_tokens() {
+        let provider = OpenAICompatibleProvider::new(&GROQ, "test-key", None).with_max_tokens(8192);
+        assert_eq!(provider.max_tokens, 8192);
+        assert!(provider.has_api_key());
+        assert!(!OpenAICompatibleProvider::new(&GROQ, "", None).has_api_key());
+    }
+
+    #[test]
+    fn test_message_conversion() {
+        let provider = OpenAICompatibleProvider::new(&GROQ, "test-key", None);
+        let messages = vec![
+            Message::system("You are a helpful assistant."),
+            Message::user("Hello"),
+            Message::assistant("Hi there!"),
+        ];
+
+        let converted = provider.convert_messages(&messages);
+        assert_eq!(converted.len(), 3);
+        assert_eq!(converted[0].role, "system");
+        assert_eq!(converted[0].content, "You are a helpful assistant.");
+        assert_eq!(converted[1].role, "user");
+        assert_eq!(converted[2].role, "assistant");
+        assert_eq!(converted[2].content, "Hi there!");
+    }
+
+    #[test]
+    fn test_extract_content_concatenates_multiple_choices() {
+        let provider = OpenAICompatibleProvider::new(&GROQ, "test-key", None);
+        let response: ChatResponse = serde_json::from_str(
+            r#"{
+                "id": "chatcmpl-1",
+                "object": "chat.completion",
+                "created": 0,
+                "model": "gpt-4o-mini",
+                "choices": [
+                    {"index": 0, "message": {"role": "assistant", "content": "SELECT 1"}, "finish_reason": "stop"},
+                    {"index": 1, "message": {"role": "assistant", "content": "SELECT 2"}, "finish_reason": "stop"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(provider.extract_content(&response).unwrap(), "SELECT 1\n\nSELECT 2");
+    }
+
+    #[test]
+    fn test_extract_content_rejects_null_first_choice() {
+        let provider = OpenAICompatibleProvider::new(&GROQ, "test-key", None);
+        let response: ChatResponse = serde_json::from_str(
+            r#"{
+                "model": "gpt-4o-mini",
+                "choices": [
+                    {"index": 0, "message": {"role": "assistant", "content": null}, "finish_reason": "tool_calls"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let error = provider.extract_content(&response).unwrap_err();
+        assert!(matches!(error, SchemaForgeError::LLMProvider { .. }));
+        assert!(error.to_string().contains("no text content"));
+    }
+
+    #[test]
+    fn test_extract_content_reports_empty_choices() {
+        let provider = OpenAICompatibleProvider::new(&GROQ, "test-key", None);
+        let response: ChatResponse =
+            serde_json::from_str(r#"{"model": "gpt-4o-mini", "choices": []}"#).unwrap();
+
+        let error = provider.extract_content(&response).unwrap_err();
+        assert!(matches!(error, SchemaForgeError::LLMResponseEmpty { .. }));
+    }
+}
@@ -0,0 +1,82 @@
+//! Token Estimation
+//!
+//! Approximate token counts for budgeting prompts without calling a
+//! provider's tokenizer. Estimates track BPE tokenizers for English text
+//! and SQL to within a few tokens per sentence.
+
+/// Estimate how many tokens `text` would use
+///
+/// Letter runs count one token plus one per further six characters, digit
+/// runs one token per three digits, and every other non-space character one
+/// token. Whitespace is free. Appending text never lowers the estimate.
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut tokens = 0;
+    let mut letters = 0;
+    let mut digits = 0;
+
+    for c in text.chars() {
+        if c.is_alphabetic() || c == '_' {
+            tokens += digit_run_tokens(std::mem::take(&mut digits));
+            letters += 1;
+        } else if c.is_numeric() {
+            tokens += letter_run_tokens(std::mem::take(&mut letters));
+            digits += 1;
+        } else {
+            tokens += letter_run_tokens(std::mem::take(&mut letters));
+            tokens += digit_run_tokens(std::mem::take(&mut digits));
+            if !c.is_whitespace() {
+                tokens += 1;
+            }
+        }
+    }
+
+    tokens + letter_run_tokens(letters) + digit_run_tokens(digits)
+}
+
+fn letter_run_tokens(length: usize) -> usize {
+    if length == 0 {
+        0
+    } else {
+        1 + (length - 1) / 6
+    }
+}
+
+fn digit_run_tokens(length: usize) -> usize {
+    length.div_ceil(3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_is_monotonic() {
+        let sentence = "Show me the ten largest customers by lifetime revenue. ";
+        let mut previous = 0;
+        for repeat in 1..=5 {
+            let estimate = estimate_tokens(&sentence.repeat(repeat));
+            assert!(estimate > previous);
+            previous = estimate;
+        }
+
+        let text = "SELECT id, name FROM users_2024 WHERE created_at > '2024-01-01';";
+        let mut previous = 0;
+        for end in text.char_indices().map(|(i, _)| i).chain([text.len()]) {
+            let estimate = estimate_tokens(&text[..end]);
+            assert!(estimate >= previous);
+            previous = estimate;
+        }
+    }
+
+    #[test]
+    fn test_estimate_calibration() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("Hello, world!"), 4);
+        // cl100k_base encodes this as 10 tokens
+        let sql = estimate_tokens("SELECT * FROM users WHERE id = 42;");
+        assert!((8..=11).contains(&sql));
+        // cl100k_base encodes this as 10 tokens
+        let prose = estimate_tokens("The quick brown fox jumps over the lazy dog.");
+        assert!((9..=12).contains(&prose));
+    }
+}
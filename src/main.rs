@@ -12,13 +12,49 @@ mod database;
 mod error;
 mod llm;
 
-use cli::Repl;
-use config::create_shared_state;
+use clap::Parser;
+use cli::commands::{self, format_error, Command};
+use cli::shutdown::{self, SessionSinks};
+use cli::{CliArgs, Repl};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Create shared application state
-    let state = create_shared_state();
+    let args = CliArgs::parse();
+
+    // Create shared application state, applying startup overrides
+    let state = args.build_state().await?;
+
+    // Run a single command and exit when --exec is given
+    if let Some(ref input) = args.exec {
+        let result = match Command::parse(input) {
+            Ok(command) => {
+                commands::handle_command_streaming(&command, state.clone(), &mut std::io::stdout())
+                    .await
+            }
+            Err(error) => Err(error),
+        };
+        let _ = shutdown::shutdown(&state, &[], &mut SessionSinks).await;
+        match result {
+            Ok(output) if output.trim().is_empty() => {}
+            Ok(output) => println!("{}", output.trim()),
+            Err(error) => {
+                eprintln!("{}", format_error(&error));
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    // SIGTERM ends the session the same way /quit does, cutting short any
+    // running command
+    #[cfg(unix)]
+    tokio::spawn(async {
+        use tokio::signal::unix::{signal, SignalKind};
+        if let Ok(mut sigterm) = signal(SignalKind::terminate()) {
+            sigterm.recv().await;
+            shutdown::request_terminate();
+        }
+    });
 
     // Create and run the REPL
     let mut repl = Repl::new(state)?;
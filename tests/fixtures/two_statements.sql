@@ -0,0 +1,7 @@
+-- Fixture for /run: creates a table and inserts one row
+CREATE TABLE notes (
+    id INTEGER PRIMARY KEY,
+    body TEXT NOT NULL
+);
+
+INSERT INTO notes (body) VALUES ('first; with a semicolon');
@@ -15,7 +15,7 @@ async fn test_command_parsing() {
 
     // Test /index parsing
     let cmd = Command::parse("/index").unwrap();
-    assert_eq!(cmd.command_type, CommandType::Index);
+    assert!(matches!(cmd.command_type, CommandType::Index { .. }));
 
     // Test /config parsing
     let cmd = Command::parse("/config anthropic test-key").unwrap();
@@ -66,6 +66,7 @@ async fn test_database_components() {
             references: None,
             is_unique: true,
             comment: None,
+            is_auto_increment: false,
         },
         Column {
             name: "name".to_string(),
@@ -82,6 +83,7 @@ async fn test_database_components() {
             references: None,
             is_unique: false,
             comment: None,
+            is_auto_increment: false,
         },
     ];
     table.primary_keys = vec!["id".to_string()];
@@ -97,6 +99,8 @@ async fn test_database_components() {
     // Test application state
     use schema_forge::config::create_shared_state;
 
+    // set_api_key saves; keep the test key out of the user's real config
+    use_temp_config_dir();
     let state = create_shared_state();
     {
         let mut state_guard = state.write().await;
@@ -118,14 +122,14 @@ async fn test_llm_provider_creation() {
     assert_eq!(anthropic.provider_name(), "Anthropic");
     assert!(anthropic.has_api_key());
 
-    let openai = schema_forge::llm::providers::openai::OpenAIProvider::new(
+    let openai = schema_forge::llm::providers::openai::provider(
         "test-key",
         None,
     );
     assert_eq!(openai.provider_name(), "OpenAI");
     assert!(openai.has_api_key());
 
-    let ollama = schema_forge::llm::providers::ollama::OllamaProvider::new(
+    let ollama = schema_forge::llm::providers::ollama::provider(
         "ollama",
         None,
     );
@@ -142,7 +146,8 @@ async fn test_sqlite_command_flow() {
 
     let connect = Command::parse(&format!("/connect {}", database.url)).unwrap();
     let connect_output = commands::handle_command(&connect, state.clone()).await.unwrap();
-    assert!(connect_output.contains("Connected to database"));
+    assert!(connect_output.contains("Connected to SQLite database"));
+    assert!(connect_output.contains("1 tables cached"));
     assert!(connect_output.contains("Indexed immediately"));
 
     let index = Command::parse("/index").unwrap();
@@ -187,6 +192,7 @@ async fn test_connect_detects_database_version() {
     let connect = Command::parse(&format!("/connect {}", database.url)).unwrap();
     let connect_output = commands::handle_command(&connect, state.clone()).await.unwrap();
     assert!(connect_output.contains("SQLite"));
+    assert!(connect_output.starts_with("Connected to SQLite database 'schema-forge-version-detect-"));
 
     let state_guard = state.read().await;
     let db_manager = state_guard.database_manager.as_ref().unwrap();
@@ -225,6 +231,278 @@ async fn test_list_tables_query_uses_sqlite_schema_without_llm() {
     assert!(output.contains("users"));
 }
 
+#[tokio::test]
+async fn test_truncated_cell_is_available_via_show() {
+    use schema_forge::config::create_shared_state;
+
+    let database = TestSqliteDatabase::new("show-cell").await;
+    let state = create_shared_state();
+
+    let connect = Command::parse(&format!("/connect {}", database.url)).unwrap();
+    commands::handle_command(&connect, state.clone()).await.unwrap();
+    state.write().await.settings.cell_width = 10;
+
+    let sql = Command::parse("SELECT id, replace(hex(zeroblob(50)), '0', 'x') AS payload FROM users WHERE id = 1").unwrap();
+    let output = commands::handle_command(&sql, state.clone()).await.unwrap();
+    assert!(output.contains("xxxxxxxxx…"));
+    assert!(!output.contains(&"x".repeat(10)));
+
+    let show = Command::parse("/show 1 payload").unwrap();
+    let value = commands::handle_command(&show, state.clone()).await.unwrap();
+    assert_eq!(value, "x".repeat(100));
+
+    let missing = Command::parse("/show 5 payload").unwrap();
+    assert!(commands::handle_command(&missing, state).await.is_err());
+}
+
+#[tokio::test]
+async fn test_run_executes_sql_file() {
+    use schema_forge::config::create_shared_state;
+
+    let database = TestSqliteDatabase::new("run-script").await;
+    let state = create_shared_state();
+
+    let connect = Command::parse(&format!("/connect {}", database.url)).unwrap();
+    commands::handle_command(&connect, state.clone()).await.unwrap();
+
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/two_statements.sql");
+    let run = Command::parse(&format!("/run {}", fixture.display())).unwrap();
+    let output = commands::handle_command(&run, state.clone()).await.unwrap();
+    assert!(output.contains("[1/2] OK"));
+    assert!(output.contains("[2/2] OK"));
+
+    let sql = Command::parse("SELECT body FROM notes").unwrap();
+    let output = commands::handle_command(&sql, state).await.unwrap();
+    assert!(output.contains("first; with a semicolon"));
+}
+
+#[test]
+fn test_exec_flag_runs_single_command() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_schema-forge"))
+        .args(["--exec", "/help"])
+        .output()
+        .expect("failed to run schema-forge binary");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Schema-Forge Commands"));
+}
+
+#[tokio::test]
+async fn test_sqlite_attached_databases_are_indexed() {
+    use schema_forge::config::create_shared_state;
+
+    let database = TestSqliteDatabase::new("attach-main").await;
+    let archive = TestSqliteDatabase::new("attach-archive").await;
+    let pool = sqlx::sqlite::SqlitePool::connect(&archive.url).await.unwrap();
+    sqlx::query("CREATE TABLE events (id INTEGER PRIMARY KEY, kind TEXT NOT NULL)")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO events (kind) VALUES ('login'), ('logout')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool.close().await;
+
+    let state = create_shared_state();
+    let connect = Command::parse(&format!(
+        "/connect {}?attach={}:archive",
+        database.url,
+        archive.path.display()
+    ))
+    .unwrap();
+    commands::handle_command(&connect, state.clone()).await.unwrap();
+
+    let schema_index = state
+        .read()
+        .await
+        .database_manager
+        .as_ref()
+        .unwrap()
+        .get_schema_index()
+        .await;
+    assert!(schema_index.tables.contains_key("users"));
+    assert!(schema_index.tables.contains_key("archive.users"));
+    let events = schema_index.get_table("archive.events").unwrap();
+    assert_eq!(events.schema.as_deref(), Some("archive"));
+    assert!(events.columns.iter().any(|column| column.name == "kind"));
+
+    let sql = Command::parse("SELECT kind FROM archive.events ORDER BY id").unwrap();
+    let output = commands::handle_command(&sql, state).await.unwrap();
+    assert!(output.contains("login"));
+}
+
+#[tokio::test]
+async fn test_sqlite_column_defaults_are_indexed() {
+    use schema_forge::database::manager::DatabaseManager;
+
+    let database = TestSqliteDatabase::new("defaults").await;
+    let manager = DatabaseManager::connect(&database.url).await.unwrap();
+    manager
+        .execute_query(
+            "CREATE TABLE orders (
+                id INTEGER,
+                region TEXT,
+                quantity INTEGER NOT NULL DEFAULT 1,
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (region, id)
+            )",
+        )
+        .await
+        .unwrap();
+
+    let schema_index = manager.index_database().await.unwrap();
+    let orders = schema_index.get_table("orders").unwrap();
+    let column = |name: &str| orders.columns.iter().find(|c| c.name == name).unwrap();
+
+    assert_eq!(column("quantity").default_value.as_deref(), Some("1"));
+    assert!(!column("quantity").nullable);
+    assert_eq!(
+        column("created_at").default_value.as_deref(),
+        Some("CURRENT_TIMESTAMP")
+    );
+    assert!(column("created_at").nullable);
+    assert_eq!(orders.primary_keys, vec!["region", "id"]);
+    assert!(column("id").is_primary_key);
+    assert!(orders.format_schema().contains("DEFAULT CURRENT_TIMESTAMP"));
+    assert!(!column("id").is_auto_increment);
+}
+
+#[tokio::test]
+async fn test_sqlite_rowid_alias_is_auto_increment() {
+    use schema_forge::database::manager::DatabaseManager;
+
+    let database = TestSqliteDatabase::new("rowid").await;
+    let manager = DatabaseManager::connect(&database.url).await.unwrap();
+    manager
+        .execute_query("CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT)")
+        .await
+        .unwrap();
+
+    let schema_index = manager.index_database().await.unwrap();
+    let notes = schema_index.get_table("notes").unwrap();
+    let column = |name: &str| notes.columns.iter().find(|c| c.name == name).unwrap();
+
+    assert!(column("id").is_auto_increment);
+    assert!(!column("body").is_auto_increment);
+    assert!(notes.format_schema().contains("id: INTEGER PRIMARY KEY AUTO_INCREMENT"));
+}
+
+#[tokio::test]
+#[ignore = "needs TURSO_DATABASE_URL and TURSO_AUTH_TOKEN for a live Turso database"]
+async fn test_libsql_remote_connection_indexes_schema() {
+    use schema_forge::database::connection::DatabaseBackend;
+    use schema_forge::database::manager::DatabaseManager;
+
+    let url = std::env::var("TURSO_DATABASE_URL").expect("TURSO_DATABASE_URL not set");
+    let token = std::env::var("TURSO_AUTH_TOKEN").ok();
+    let manager = DatabaseManager::connect_with_auth_token(&url, token).await.unwrap();
+    assert_eq!(manager.backend(), DatabaseBackend::SQLite);
+
+    manager
+        .execute_query("CREATE TABLE IF NOT EXISTS schema_forge_probe (id INTEGER PRIMARY KEY, note TEXT)")
+        .await
+        .unwrap();
+    let schema_index = manager.index_database().await.unwrap();
+    let probe = schema_index.get_table("schema_forge_probe").unwrap();
+    assert_eq!(probe.primary_keys, vec!["id".to_string()]);
+    assert!(probe.columns.iter().any(|column| column.name == "note"));
+
+    let result = manager
+        .execute_query_buffered("SELECT count(*) AS n FROM schema_forge_probe")
+        .await
+        .unwrap();
+    assert_eq!(result.columns, vec!["n".to_string()]);
+
+    manager.execute_query("DROP TABLE schema_forge_probe").await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs MYSQL_DATABASE_URL for a live MySQL database"]
+async fn test_mysql_default_schema_is_connected_database() {
+    use schema_forge::database::manager::DatabaseManager;
+
+    let url = std::env::var("MYSQL_DATABASE_URL").expect("MYSQL_DATABASE_URL not set");
+    let expected = url.rsplit('/').next().unwrap().split('?').next().unwrap().to_string();
+    let manager = DatabaseManager::connect(&url).await.unwrap();
+    assert_eq!(manager.default_schema(), Some(expected.as_str()));
+
+    let schema_index = manager.index_database().await.unwrap();
+    assert_eq!(schema_index.schema_name.as_deref(), Some(expected.as_str()));
+    assert_eq!(schema_index.database_name.as_deref(), Some(expected.as_str()));
+}
+
+#[tokio::test]
+#[ignore = "needs POSTGRES_DATABASE_URL for a live PostgreSQL database"]
+async fn test_postgres_partitions_are_annotated() {
+    use schema_forge::database::manager::DatabaseManager;
+
+    let url = std::env::var("POSTGRES_DATABASE_URL").expect("POSTGRES_DATABASE_URL not set");
+    let manager = DatabaseManager::connect(&url).await.unwrap();
+    for statement in [
+        "DROP TABLE IF EXISTS schema_forge_events CASCADE",
+        "CREATE TABLE schema_forge_events (id bigint, created_at date NOT NULL) PARTITION BY RANGE (created_at)",
+        "CREATE TABLE schema_forge_events_2024 PARTITION OF schema_forge_events FOR VALUES FROM ('2024-01-01') TO ('2025-01-01')",
+    ] {
+        manager.execute_query(statement).await.unwrap();
+    }
+
+    let schema_index = manager.index_database().await.unwrap();
+    let parent = schema_index.get_table("schema_forge_events").unwrap();
+    assert!(!parent.is_partition);
+    assert_eq!(parent.partition_of, None);
+    let partition = schema_index.get_table("schema_forge_events_2024").unwrap();
+    assert!(partition.is_partition);
+    assert_eq!(partition.partition_of.as_deref(), Some("schema_forge_events"));
+    assert!(partition
+        .format_schema()
+        .contains("schema_forge_events_2024 is a partition of schema_forge_events"));
+    assert!(schema_index
+        .format_summary_for_llm()
+        .contains("partitions: schema_forge_events_2024"));
+
+    manager
+        .execute_query("DROP TABLE schema_forge_events CASCADE")
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs POSTGRES_DATABASE_URL for a live PostgreSQL database"]
+async fn test_postgres_statement_timeout_is_in_effect() {
+    use schema_forge::database::manager::DatabaseManager;
+
+    let url = std::env::var("POSTGRES_DATABASE_URL").expect("POSTGRES_DATABASE_URL not set");
+    let mut manager = DatabaseManager::connect_with_statement_timeout(&url, None, Some(250))
+        .await
+        .unwrap();
+    let result = manager
+        .execute_query_buffered("SHOW statement_timeout")
+        .await
+        .unwrap();
+    assert_eq!(result.rows[0][0], "250ms");
+    assert!(manager.execute_query("SELECT pg_sleep(2)").await.is_err());
+
+    // Changing it reopens the pool with the new value
+    manager.set_statement_timeout(None).await.unwrap();
+    let result = manager
+        .execute_query_buffered("SHOW statement_timeout")
+        .await
+        .unwrap();
+    assert_eq!(result.rows[0][0], "0");
+}
+
+/// Point this test binary's config directory at a temporary directory
+fn use_temp_config_dir() {
+    use schema_forge::config::storage::Config;
+
+    let _ = Config::set_config_dir(std::env::temp_dir().join(format!(
+        "schema-forge-integration-config-{}",
+        std::process::id()
+    )));
+}
+
 struct TestSqliteDatabase {
     path: PathBuf,
     url: String,